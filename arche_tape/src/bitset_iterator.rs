@@ -43,13 +43,27 @@ mod bitset_iterator {
         fn next(&mut self) -> Option<Self::Item> {
             loop {
                 if self.bits_remaining == 0 {
-                    // We have to initialise filtered to a proper value so we hand write the first iteration of the loop :(
-                    let mut iter = self.iters.borrow_mut().iter_mut();
-                    let (first_iter, first_map) = iter.next()?;
-                    let mut filtered: usize = first_map(*first_iter.next()?);
+                    if self.index >= self.bit_length as usize {
+                        return None;
+                    }
+
+                    // Bitsets that have run out are treated as all zeros before being mapped so that a negated
+                    // bitset that is shorter than the rest doesn't end the iteration early
+                    let mut filtered = usize::MAX;
+                    let mut exhausted = true;
+                    for (iter, map) in self.iters.borrow_mut().iter_mut() {
+                        let bits = match iter.next() {
+                            Some(&bits) => {
+                                exhausted = false;
+                                bits
+                            }
+                            None => 0,
+                        };
+                        filtered &= map(bits);
+                    }
 
-                    for (iter, map) in iter {
-                        filtered &= map(*iter.next()?);
+                    if exhausted {
+                        return None;
                     }
 
                     self.bits_remaining = usize::BITS;
@@ -72,11 +86,9 @@ mod bitset_iterator {
                 self.index += zeros as usize + 1;
 
                 if self.index > self.bit_length as usize {
-                    // Hack but make sure that calling next() after None is returned continues to return None by
-                    // setting an empty iterator and making the subsequent Next calls try and call next() on it
+                    // Make sure that calling next() after None is returned continues to return None, the index is
+                    // already past bit_length so the next refill of current_bits will return None
                     self.current_bits = 0;
-                    // Indexing by 0 should always be fine since an empty slice will never make it this far
-                    self.iters.borrow_mut()[0].0 = [].iter();
                     return None;
                 }
 
@@ -86,6 +98,7 @@ mod bitset_iterator {
     }
 }
 
+#[derive(Clone)]
 pub struct Bitvec {
     pub(crate) data: Vec<usize>,
     /// Length in bits of the bitvec
@@ -142,22 +155,33 @@ impl Bitvec {
     }
 }
 
+#[derive(Clone)]
 pub struct Bitsetsss {
     bitsets: Vec<Bitvec>,
+    /// Pairs share their index with their target so their bitvecs are stored separately
+    pairs: HashMap<EcsId, Bitvec>,
+    /// The keys of ``pairs`` indexed by the index of their relation and of their target, so that despawning an entity
+    /// only has to look at its own pairs
+    pairs_of: HashMap<usize, Vec<EcsId>>,
 }
 
 use crate::EcsId;
+use std::collections::HashMap;
 impl Bitsetsss {
     #[allow(unused)]
     pub(crate) fn new() -> Self {
         Self {
             bitsets: Vec::new(),
+            pairs: HashMap::new(),
+            pairs_of: HashMap::new(),
         }
     }
 
     pub(crate) fn with_capacity(cap: usize) -> Self {
         Self {
             bitsets: Vec::with_capacity(cap),
+            pairs: HashMap::new(),
+            pairs_of: HashMap::new(),
         }
     }
 
@@ -172,25 +196,71 @@ impl Bitsetsss {
     }
 
     pub(crate) fn get_bitvec(&self, comp_id: EcsId) -> Option<&Bitvec> {
+        if comp_id.is_pair() {
+            return self.pairs.get(&comp_id);
+        }
         self.bitsets.get(comp_id.uindex())
     }
 
-    pub(crate) fn set_bit(&mut self, entity: EcsId, index: usize, value: bool) {
-        if entity.uindex() >= self.bitsets.len() {
-            self.insert_bitvec(entity);
+    fn get_bitvec_mut(&mut self, comp_id: EcsId) -> &mut Bitvec {
+        if comp_id.is_pair() {
+            if !self.pairs.contains_key(&comp_id) {
+                self.pairs_of
+                    .entry(comp_id.relation_index())
+                    .or_default()
+                    .push(comp_id);
+                if !comp_id.is_wildcard() {
+                    self.pairs_of
+                        .entry(comp_id.uindex())
+                        .or_default()
+                        .push(comp_id);
+                }
+            }
+            return self.pairs.entry(comp_id).or_insert_with(Bitvec::new);
+        }
+        if comp_id.uindex() >= self.bitsets.len() {
+            self.insert_bitvec(comp_id);
         }
+        &mut self.bitsets[comp_id.uindex()]
+    }
 
-        let bitvec = &mut self.bitsets[entity.uindex()];
-        bitvec.set_bit(index, value);
+    pub(crate) fn set_bit(&mut self, entity: EcsId, index: usize, value: bool) {
+        self.get_bitvec_mut(entity).set_bit(index, value);
     }
 
     #[allow(unused)]
     pub(crate) fn push_bit(&mut self, entity: EcsId, value: bool) {
-        if entity.uindex() >= self.bitsets.len() {
-            self.insert_bitvec(entity);
+        self.get_bitvec_mut(entity).push_bit(value);
+    }
+
+    /// Sets the bit of an archetype in the bitvec of each of its components, pairs also set the bit in the bitvec of
+    /// their relation's wildcard pair
+    pub(crate) fn set_archetype_bits(&mut self, comp_ids: &[EcsId], archetype: usize, value: bool) {
+        for &id in comp_ids {
+            self.set_bit(id, archetype, value);
+            if id.is_pair() {
+                self.set_bit(id.wildcard(), archetype, value);
+            }
         }
+    }
+
+    /// Every pair, including wildcard pairs, that has had a bitvec created for it and has the entity at ``index`` as
+    /// its relation or target
+    pub(crate) fn pair_ids_of(&self, index: usize) -> &[EcsId] {
+        self.pairs_of.get(&index).map_or(&[], Vec::as_slice)
+    }
 
-        let bitvec = &mut self.bitsets[entity.uindex()];
-        bitvec.push_bit(value);
+    pub(crate) fn remove_pair_bitvec(&mut self, pair: EcsId) {
+        if self.pairs.remove(&pair).is_none() {
+            return;
+        }
+        for index in [pair.relation_index(), pair.uindex()] {
+            if let Some(pairs) = self.pairs_of.get_mut(&index) {
+                pairs.retain(|&id| id != pair);
+                if pairs.is_empty() {
+                    self.pairs_of.remove(&index);
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,50 @@
+use crate::{Component, EcsId, World};
+use std::any::TypeId;
+
+/// A group of components that can be added to or removed from an entity with a single archetype move,
+/// see ``World::add_bundle`` and ``World::remove_bundle``
+pub trait Bundle: 'static {
+    type Ptrs: AsRef<[*mut u8]>;
+
+    /// Registers every component type in the bundle and returns their ids in the same order as the tuple
+    fn comp_ids(world: &mut World) -> Vec<EcsId>;
+
+    /// The ``TypeId``s of the components in the same order as the tuple
+    fn type_ids() -> Vec<TypeId>;
+
+    /// Pointers to each component in the same order as the tuple, the components are still owned by ``self``
+    fn component_ptrs(&mut self) -> Self::Ptrs;
+}
+
+macro_rules! impl_bundle {
+    ($($T:ident)* $N:literal) => {
+        impl<$($T: Component),*> Bundle for ($($T,)*) {
+            type Ptrs = [*mut u8; $N];
+
+            fn comp_ids(world: &mut World) -> Vec<EcsId> {
+                vec![$(world.get_or_create_type_id_ecsid::<$T>(),)*]
+            }
+
+            fn type_ids() -> Vec<TypeId> {
+                vec![$(TypeId::of::<$T>(),)*]
+            }
+
+            #[allow(non_snake_case)]
+            fn component_ptrs(&mut self) -> Self::Ptrs {
+                let ($($T,)*) = self;
+                [$($T as *mut $T as *mut u8,)*]
+            }
+        }
+    };
+}
+
+impl_bundle!(A B C D E F G H J K 10);
+impl_bundle!(A B C D E F G H J 9);
+impl_bundle!(A B C D E F G H 8);
+impl_bundle!(A B C D E F G 7);
+impl_bundle!(A B C D E F 6);
+impl_bundle!(A B C D E 5);
+impl_bundle!(A B C D 4);
+impl_bundle!(A B C 3);
+impl_bundle!(A B 2);
+impl_bundle!(A 1);
@@ -0,0 +1,282 @@
+use crate::entities::Entities;
+use crate::world::{
+    ArchIndex, Archetype, ArchetypeEdges, Column, ComponentTicks, EntityMeta, InstanceMeta,
+};
+use crate::{CloneError, EcsId, World};
+use std::alloc::Layout;
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::RwLock;
+use untyped_vec::TypeInfo;
+
+type CloneFn = fn(*const u8, *mut MaybeUninit<u8>);
+type DropFn = fn(*mut MaybeUninit<u8>);
+/// The clone fn, layout and drop fn of a column
+type ColumnCloneFns = (Option<CloneFn>, Layout, Option<DropFn>);
+
+/// Pushes a clone of ``row`` of ``src`` onto the end of ``dst``, unit components have no ``clone_fn``
+///
+/// # Safety
+///
+///    ``clone_fn`` must be the clone fn of the columns' component and ``buffer`` must be at least as large as it
+unsafe fn push_clone(
+    src: &Column,
+    dst: &mut Column,
+    row: usize,
+    clone_fn: Option<CloneFn>,
+    buffer: &mut [MaybeUninit<u8>],
+    ticks: ComponentTicks,
+) {
+    match clone_fn {
+        Some(clone_fn) => {
+            clone_fn(src.data.get_raw(row).unwrap(), buffer.as_mut_ptr());
+            unsafe { dst.push_raw(buffer.as_mut_ptr(), ticks) };
+        }
+        None => {
+            let dangling = core::ptr::NonNull::dangling().as_ptr();
+            unsafe { dst.push_raw(dangling, ticks) };
+        }
+    }
+}
+
+/// A clone of one component of an entity that hasn't been pushed onto a column yet. Clones that are never pushed,
+/// because cloning another component failed or panicked, are dropped along with this
+struct StagedClone {
+    ptr: *mut MaybeUninit<u8>,
+    layout: Layout,
+    drop_fn: Option<DropFn>,
+    /// Whether ``ptr`` holds a clone that hasn't been moved onto a column
+    initialized: bool,
+}
+
+impl StagedClone {
+    /// # Safety
+    ///
+    ///    ``clone_fn``, ``layout`` and ``drop_fn`` must be those of the column's component
+    unsafe fn new(
+        column: &Column,
+        row: usize,
+        clone_fn: Option<CloneFn>,
+        layout: Layout,
+        drop_fn: Option<DropFn>,
+    ) -> Self {
+        let ptr = match layout.size() {
+            0 => layout.align() as *mut MaybeUninit<u8>,
+            _ => {
+                let ptr = unsafe { std::alloc::alloc(layout) };
+                if ptr.is_null() {
+                    std::alloc::handle_alloc_error(layout);
+                }
+                ptr as *mut MaybeUninit<u8>
+            }
+        };
+        let mut staged = Self {
+            ptr,
+            layout,
+            drop_fn,
+            initialized: false,
+        };
+        if let Some(clone_fn) = clone_fn {
+            clone_fn(column.data.get_raw(row).unwrap(), staged.ptr);
+            staged.initialized = true;
+        }
+        staged
+    }
+
+    /// # Safety
+    ///
+    ///    ``column`` must store the component that was cloned
+    unsafe fn push(mut self, column: &mut Column, ticks: ComponentTicks) {
+        unsafe { column.push_raw(self.ptr, ticks) };
+        self.initialized = false;
+    }
+}
+
+impl Drop for StagedClone {
+    fn drop(&mut self) {
+        if let (true, Some(drop_fn)) = (self.initialized, self.drop_fn) {
+            drop_fn(self.ptr);
+        }
+        if self.layout.size() != 0 {
+            unsafe { std::alloc::dealloc(self.ptr as *mut u8, self.layout) };
+        }
+    }
+}
+
+impl World {
+    /// Panicking version of ``World::try_clone_entity``
+    pub fn clone_entity(&mut self, entity: EcsId) -> EcsId {
+        self.try_clone_entity(entity)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Spawns a copy of ``entity`` with a clone of each of its components, the clones are marked as added at the
+    /// current change tick. Every component of the entity that holds data must be cloneable, see
+    /// ``ComponentMeta::from_generic_clone``
+    ///
+    /// With the ``hierarchy`` feature a clone of a child is added to the children of the same parent, and the
+    /// descendants of ``entity`` are cloned along with it as the children of its clone. Every descendant must be
+    /// cloneable too
+    pub fn try_clone_entity(&mut self, entity: EcsId) -> Result<EcsId, CloneError> {
+        self.flush_reserved();
+        if !self.is_alive(entity) {
+            return Err(CloneError::NoSuchEntity);
+        }
+        // Checked up front so that no descendant is cloned if one of them can't be
+        #[cfg(feature = "hierarchy")]
+        for descendant in self.descendants_depth_first(entity) {
+            self.clone_fns(descendant)?;
+        }
+
+        let clone = self.clone_single_entity(entity)?;
+        #[cfg(feature = "hierarchy")]
+        {
+            self.attach_clone_to_parent(clone);
+            self.clone_children(entity, clone);
+        }
+        Ok(clone)
+    }
+
+    /// Clones ``entity`` without touching the hierarchy, the clone keeps the same ``Parent`` and ``Children``
+    pub(crate) fn clone_single_entity(&mut self, entity: EcsId) -> Result<EcsId, CloneError> {
+        let meta = self
+            .get_entity_meta(entity)
+            .ok_or(CloneError::NoSuchEntity)?
+            .clone();
+        let ArchIndex(archetype_idx) = meta.instance_meta.archetype;
+        let clone_fns = self.clone_fns(entity)?;
+
+        // Every component is cloned before anything is spawned or pushed so that a panicking clone leaves the world
+        // as it was
+        let archetype = &self.archetypes[archetype_idx];
+        let mut staged = Vec::with_capacity(clone_fns.len());
+        for ((_, column), (clone_fn, layout, drop_fn)) in
+            archetype.component_storages.iter().zip(clone_fns)
+        {
+            // Safe because this takes &mut self so nothing is writing to the column
+            let column = unsafe { &*column.get() };
+            let row = meta.instance_meta.index;
+            staged.push(unsafe { StagedClone::new(column, row, clone_fn, layout, drop_fn) });
+        }
+
+        let clone = self.entities.spawn();
+        let ticks = ComponentTicks::new(self.change_tick());
+        let archetype = &mut self.archetypes[archetype_idx];
+        for ((_, column), staged) in archetype.component_storages.iter_mut().zip(staged) {
+            unsafe { staged.push(column.get_mut(), ticks) };
+        }
+        archetype.entities.push(clone);
+
+        let clone_meta = EntityMeta {
+            instance_meta: InstanceMeta {
+                archetype: ArchIndex(archetype_idx),
+                index: archetype.entities.len() - 1,
+            },
+            component_meta: meta.component_meta,
+        };
+        self.set_entity_meta(clone, clone_meta);
+        Ok(clone)
+    }
+
+    /// Clones every entity and component into a new world with the same ids and ticks, every column that isn't empty
+    /// must be cloneable. Every component is locked while the world is cloned so this fails if any of them is
+    /// mutably borrowed by a query
+    pub fn try_clone(&self) -> Result<World, CloneError> {
+        let _guards = self
+            .lock_lookup
+            .keys()
+            .map(|&id| self.try_lock(id, false))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(CloneError::Borrow)?;
+
+        let mut archetypes = Vec::with_capacity(self.archetypes.len());
+        for archetype in self.archetypes.iter() {
+            let mut component_storages = Vec::with_capacity(archetype.component_storages.len());
+            for (id, column) in archetype.component_storages.iter() {
+                // Safe because the read lock is held so nothing is writing to the column
+                let column = unsafe { &*column.get() };
+                let meta = self.get_component_meta(*id).unwrap();
+                let mut clone = unsafe { Column::new(TypeInfo::new(meta.layout, meta.drop_fn)) };
+                if !column.is_empty() {
+                    let clone_fn = self.clone_fn(*id)?;
+                    let mut buffer = vec![MaybeUninit::uninit(); meta.layout.size()];
+                    for row in 0..column.len() {
+                        let ticks = column.ticks(row);
+                        unsafe {
+                            push_clone(column, &mut clone, row, clone_fn, &mut buffer, ticks)
+                        };
+                    }
+                }
+                component_storages.push((*id, UnsafeCell::new(clone)));
+            }
+
+            archetypes.push(Archetype {
+                comp_lookup: archetype.comp_lookup.clone(),
+                entities: archetype.entities.clone(),
+                component_storages,
+                comp_ids: archetype.comp_ids.clone(),
+                add_edges: ArchetypeEdges::new(),
+                remove_edges: ArchetypeEdges::new(),
+            });
+        }
+
+        Ok(World {
+            archetypes,
+            archetype_lookup: self.archetype_lookup.clone(),
+            archetype_bitset: self.archetype_bitset.clone(),
+            entities_bitvec: self.entities_bitvec.clone(),
+
+            entities: Entities {
+                generations: self.entities.generations.clone(),
+                despawned: self.entities.despawned.clone(),
+                reserved: AtomicUsize::new(self.entities.reserved.load(Ordering::Relaxed)),
+            },
+
+            ecs_id_meta: self.ecs_id_meta.clone(),
+            type_id_to_ecs_id: self.type_id_to_ecs_id.clone(),
+
+            lock_lookup: self.lock_lookup.clone(),
+            locks: self.locks.iter().map(|_| RwLock::new(())).collect(),
+
+            change_tick: AtomicU32::new(self.change_tick()),
+
+            removed_components: self.removed_components.clone(),
+            despawned: self.despawned.clone(),
+
+            resource_entity: self.resource_entity,
+
+            #[cfg(feature = "serde")]
+            component_registry: self.component_registry.clone(),
+
+            entity_builder_reuse: None,
+        })
+    }
+
+    /// The ``clone_fn`` of a component, None for unit components as they have no data to clone
+    /// The clone fn, layout and drop fn of each component of ``entity`` in the order of its archetype's columns
+    fn clone_fns(&self, entity: EcsId) -> Result<Vec<ColumnCloneFns>, CloneError> {
+        let ArchIndex(archetype_idx) = self
+            .get_entity_meta(entity)
+            .unwrap()
+            .instance_meta
+            .archetype;
+        self.archetypes[archetype_idx]
+            .comp_ids
+            .iter()
+            .map(|&id| {
+                let meta = self.get_component_meta(id).unwrap();
+                Ok((self.clone_fn(id)?, meta.layout, meta.drop_fn))
+            })
+            .collect()
+    }
+
+    fn clone_fn(&self, comp_id: EcsId) -> Result<Option<CloneFn>, CloneError> {
+        let meta = self.get_component_meta(comp_id).unwrap();
+        match (meta.is_unit, meta.clone_fn) {
+            (true, _) => Ok(None),
+            (false, Some(clone_fn)) => Ok(Some(clone_fn)),
+            (false, None) => Err(CloneError::NotCloneable(comp_id)),
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    any::TypeId,
+    mem::{ManuallyDrop, MaybeUninit},
+};
+
+use crate::{utils::drop_unaligned, world::ComponentMeta, Component, EcsError, EcsId, World};
+
+/// The component a command refers to, typed components are resolved to an EcsId when the buffer is applied
+/// because creating the EcsId for a type requires mutable access to the world
+enum CommandComponent {
+    Typed(TypeId, ComponentMeta),
+    Dynamic(EcsId),
+}
+
+enum Command {
+    Despawn(EcsId),
+    AddComponent {
+        entity: EcsId,
+        component: CommandComponent,
+        /// Offset into ``CommandBuffer::data`` and the meta of the data stored there, None for dataless components
+        data: Option<(usize, ComponentMeta)>,
+    },
+    RemoveComponent {
+        entity: EcsId,
+        component: CommandComponent,
+    },
+}
+
+/// Records structural changes to a world so that they can be applied later with ``World::apply``,
+/// this allows spawning/despawning and adding/removing components while iterating a query
+pub struct CommandBuffer {
+    commands: Vec<Command>,
+    /// Component data is stored unaligned, it is only ever copied out with ``copy_nonoverlapping``
+    data: Vec<MaybeUninit<u8>>,
+}
+
+impl Drop for CommandBuffer {
+    fn drop(&mut self) {
+        for command in self.commands.drain(..) {
+            if let Command::AddComponent {
+                data: Some((offset, meta)),
+                ..
+            } = command
+            {
+                // Safe because the data at offset was pushed with this meta and never moved out
+                unsafe { drop_unaligned(self.data.as_mut_ptr().add(offset) as *mut u8, &meta) }
+            }
+        }
+    }
+}
+
+impl Default for CommandBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandBuffer {
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Reserves an EcsId from ``world`` and records spawning it, the returned builder can be used to add components
+    ///
+    /// The id is valid to use in later commands of this buffer. If the buffer is never applied the entity will still be
+    /// spawned with no components
+    #[must_use]
+    pub fn spawn<'a>(&'a mut self, world: &'a World) -> CommandEntityBuilder<'a> {
+        let entity = world.reserve_entity();
+        CommandEntityBuilder {
+            buffer: self,
+            world,
+            entity,
+        }
+    }
+
+    pub fn despawn(&mut self, entity: EcsId) {
+        self.commands.push(Command::Despawn(entity));
+    }
+
+    pub fn add_component<T: Component>(&mut self, entity: EcsId, component: T) {
+        let meta = ComponentMeta::from_generic::<T>();
+        let mut component = ManuallyDrop::new(component);
+        // Safe because component is a valid T and is never used again
+        let offset = unsafe { self.push_data(&mut component as *mut _ as *mut u8, &meta) };
+        self.commands.push(Command::AddComponent {
+            entity,
+            component: CommandComponent::Typed(TypeId::of::<T>(), meta.clone()),
+            data: Some((offset, meta)),
+        });
+    }
+
+    pub fn remove_component<T: Component>(&mut self, entity: EcsId) {
+        self.commands.push(Command::RemoveComponent {
+            entity,
+            component: CommandComponent::Typed(
+                TypeId::of::<T>(),
+                ComponentMeta::from_generic::<T>(),
+            ),
+        });
+    }
+
+    /// Records adding an entity as a dataless component
+    ///
+    /// Applying this command will panic if a component with the ID of component_id expects data.
+    pub fn add_component_dynamic(&mut self, entity: EcsId, comp_id: EcsId) {
+        self.commands.push(Command::AddComponent {
+            entity,
+            component: CommandComponent::Dynamic(comp_id),
+            data: None,
+        });
+    }
+
+    /// # Safety
+    ///
+    ///   ``component_ptr`` must point to data that matches the component_meta of comp_id in ``world``.
+    ///   The data must also not be used after calling this function.
+    pub unsafe fn add_component_dynamic_with_data(
+        &mut self,
+        world: &World,
+        entity: EcsId,
+        comp_id: EcsId,
+        component_ptr: *mut u8,
+    ) {
+        let meta = world
+            .get_component_meta(comp_id)
+            .expect("Dead entity may not be used as a component")
+            .clone();
+        let offset = unsafe { self.push_data(component_ptr, &meta) };
+        self.commands.push(Command::AddComponent {
+            entity,
+            component: CommandComponent::Dynamic(comp_id),
+            data: Some((offset, meta)),
+        });
+    }
+
+    pub fn remove_component_dynamic(&mut self, entity: EcsId, comp_id: EcsId) {
+        self.commands.push(Command::RemoveComponent {
+            entity,
+            component: CommandComponent::Dynamic(comp_id),
+        });
+    }
+
+    /// # Safety
+    ///
+    ///   ``component`` must point to a valid instance of the type described by ``meta`` and must not be used again
+    unsafe fn push_data(&mut self, component: *mut u8, meta: &ComponentMeta) -> usize {
+        let offset = self.data.len();
+        let size = meta.layout.size();
+        self.data.reserve(size);
+        unsafe {
+            std::ptr::copy_nonoverlapping::<MaybeUninit<u8>>(
+                component as *mut _,
+                self.data.as_mut_ptr().add(offset),
+                size,
+            );
+            self.data.set_len(offset + size);
+        }
+        offset
+    }
+
+    pub(crate) fn apply(&mut self, world: &mut World) {
+        let data = self.data.as_mut_ptr();
+        for command in self.commands.drain(..) {
+            match command {
+                Command::Despawn(entity) => {
+                    world.despawn(entity);
+                }
+                Command::AddComponent {
+                    entity,
+                    component,
+                    data: component_data,
+                } => {
+                    let comp_id = match component {
+                        CommandComponent::Typed(type_id, meta) => {
+                            world.get_or_create_type_id_ecsid_dynamic(type_id, meta)
+                        }
+                        CommandComponent::Dynamic(comp_id) => comp_id,
+                    };
+
+                    match component_data {
+                        Some((offset, meta)) => {
+                            // Safe because the data at offset was pushed with the meta of comp_id
+                            let ptr = unsafe { data.add(offset) as *mut u8 };
+                            // The data is still owned by the buffer if it couldn't be added, such as when the entity
+                            // is dead or already has the component
+                            let added = unsafe {
+                                world.try_add_component_dynamic_with_data(entity, comp_id, ptr)
+                            };
+                            if added.is_err() {
+                                unsafe { drop_unaligned(ptr, &meta) }
+                            }
+                        }
+                        None => match world.try_add_component_dynamic(entity, comp_id) {
+                            Ok(())
+                            | Err(EcsError::NoSuchEntity)
+                            | Err(EcsError::AlreadyPresent) => (),
+                            Err(err) => panic!("{}", err),
+                        },
+                    }
+                }
+                Command::RemoveComponent { entity, component } => {
+                    let comp_id = match component {
+                        CommandComponent::Typed(type_id, _) => {
+                            match world.type_id_to_ecs_id.get(&type_id) {
+                                Some(&comp_id) => comp_id,
+                                // The type was never added to any entity so there is nothing to remove
+                                None => continue,
+                            }
+                        }
+                        CommandComponent::Dynamic(comp_id) => comp_id,
+                    };
+                    // Removing a component the entity doesn't have is ignored like despawning a dead entity
+                    match world.try_remove_component_dynamic(entity, comp_id) {
+                        Ok(()) | Err(EcsError::NoSuchEntity) | Err(EcsError::Missing) => (),
+                        Err(err) => panic!("{}", err),
+                    }
+                }
+            }
+        }
+        self.data.clear();
+    }
+}
+
+pub struct CommandEntityBuilder<'a> {
+    buffer: &'a mut CommandBuffer,
+    world: &'a World,
+    entity: EcsId,
+}
+
+impl<'a> CommandEntityBuilder<'a> {
+    #[must_use]
+    pub fn with<C: Component>(self, component: C) -> Self {
+        self.buffer.add_component(self.entity, component);
+        self
+    }
+
+    /// Adds an entity as a dataless component
+    #[must_use]
+    pub fn with_dynamic(self, component_id: EcsId) -> Self {
+        self.buffer.add_component_dynamic(self.entity, component_id);
+        self
+    }
+
+    /// # Safety
+    ///
+    ///    data behind ``component`` must not be used again.
+    ///    data behind ``component`` must be a valid instance of the type given by ``component_id``
+    #[must_use]
+    pub unsafe fn with_dynamic_with_data(self, component: *mut u8, component_id: EcsId) -> Self {
+        unsafe {
+            self.buffer.add_component_dynamic_with_data(
+                self.world,
+                self.entity,
+                component_id,
+                component,
+            )
+        };
+        self
+    }
+
+    /// Returns the reserved id of the entity being spawned
+    pub fn build(self) -> EcsId {
+        self.entity
+    }
+}
@@ -1,33 +1,112 @@
 use crate::utils::EitherGuard;
-use crate::{world::Archetype, EcsId, World};
-use std::marker::PhantomData;
+use crate::{world::Archetype, BorrowError, EcsId, World};
+use std::{marker::PhantomData, slice::Iter};
+
+/// Returns a pointer to the first row of a fetch in an archetype and the offset to the next row
+type CreatePtrFn = fn(&World, &Archetype, Option<EcsId>) -> (*mut u8, usize);
 
 struct IntraArchetypeIter<'a, const N: usize> {
     remaining: usize,
 
     ptrs: [*mut u8; N],
     offsets: [usize; N],
+    ticks: [*mut u32; N],
+    /// False when no fetch is ``Added`` or ``Changed`` so rows can be returned without looking at their ticks
+    uses_ticks: bool,
+
+    fetches: Option<&'a [FetchType; N]>,
+    last_run: u32,
+    change_tick: u32,
 
     phantom: PhantomData<&'a mut Archetype>,
 }
 
 impl<'a, const N: usize> IntraArchetypeIter<'a, N> {
+    /// Iterates every row of ``archetype``
+    ///
+    /// Without ``Added`` or ``Changed`` fetches every row is returned so mutably fetched columns are marked as changed
+    /// here in one fill of their ticks, even if the iterator is dropped before reaching them
+    #[inline(never)]
+    fn new(
+        archetype: &Archetype,
+        ptrs: [*mut u8; N],
+        offsets: [usize; N],
+        fetches: &'a [FetchType; N],
+        last_run: u32,
+        change_tick: u32,
+    ) -> Self {
+        let mut ticks = [core::ptr::null_mut(); N];
+        for (ticks, fetch) in ticks.iter_mut().zip(fetches.iter()) {
+            *ticks = fetch.create_ticks_ptr(archetype);
+        }
+        let uses_ticks = fetches
+            .iter()
+            .any(|fetch| matches!(fetch, FetchType::Added(_) | FetchType::Changed(_)));
+        if !uses_ticks {
+            for (fetch, &ticks) in fetches.iter().zip(ticks.iter()) {
+                fetch.update_all_ticks(ticks, archetype.entities.len(), change_tick);
+            }
+        }
+
+        Self {
+            remaining: archetype.entities.len(),
+            ptrs,
+            offsets,
+            ticks,
+            uses_ticks,
+            fetches: Some(fetches),
+            last_run,
+            change_tick,
+            phantom: PhantomData,
+        }
+    }
+
     /// Empty iterator
     fn unit() -> Self {
         Self {
             remaining: 0,
             ptrs: [0x0 as _; N],
             offsets: [0; N],
+            ticks: [0x0 as _; N],
+            uses_ticks: false,
+            fetches: None,
+            last_run: 0,
+            change_tick: 0,
             phantom: PhantomData,
         }
     }
 
-    fn new(length: usize, ptrs: [*mut u8; N], offsets: [usize; N]) -> Self {
-        Self {
-            remaining: length,
-            ptrs,
-            offsets,
-            phantom: PhantomData,
+    /// ``Iterator::next`` for queries with ``Added`` or ``Changed`` fetches, rows are skipped until one matches
+    fn next_filtered(&mut self) -> Option<[*mut u8; N]> {
+        loop {
+            if self.remaining == 0 {
+                return None;
+            }
+
+            let ptrs = self.ptrs;
+            let ticks = self.ticks;
+
+            for (ptr, offset) in self.ptrs.iter_mut().zip(self.offsets.iter()) {
+                unsafe { *ptr = ptr.add(*offset) }
+            }
+            for ticks in self.ticks.iter_mut().filter(|ticks| !ticks.is_null()) {
+                unsafe { *ticks = ticks.add(1) }
+            }
+            self.remaining -= 1;
+
+            let fetches = self.fetches.unwrap();
+            let matches = fetches
+                .iter()
+                .zip(ticks.iter())
+                .all(|(fetch, &ticks)| fetch.filter_ticks(ticks, self.last_run));
+            if !matches {
+                continue;
+            }
+
+            for (fetch, &ticks) in fetches.iter().zip(ticks.iter()) {
+                fetch.update_ticks(ticks, self.change_tick);
+            }
+            return Some(ptrs);
         }
     }
 }
@@ -36,29 +115,59 @@ impl<'a, const N: usize> Iterator for IntraArchetypeIter<'a, N> {
     type Item = [*mut u8; N];
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.uses_ticks {
+            return self.next_filtered();
+        }
+
         if self.remaining == 0 {
             return None;
         }
 
         let ptrs = self.ptrs;
-
         for (ptr, offset) in self.ptrs.iter_mut().zip(self.offsets.iter()) {
             unsafe { *ptr = ptr.add(*offset) }
         }
         self.remaining -= 1;
-
         Some(ptrs)
     }
+
+    fn fold<B, F>(mut self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let mut acc = init;
+        if self.uses_ticks {
+            while let Some(ptrs) = self.next_filtered() {
+                acc = f(acc, ptrs);
+            }
+            return acc;
+        }
+
+        for _ in 0..self.remaining {
+            let ptrs = self.ptrs;
+            for (ptr, offset) in self.ptrs.iter_mut().zip(self.offsets.iter()) {
+                unsafe { *ptr = ptr.add(*offset) }
+            }
+            acc = f(acc, ptrs);
+        }
+        acc
+    }
 }
 
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub struct PtrLen(*mut u8, usize);
 
+/// Yields the columns of every matching archetype, the column iterator can't skip individual rows so ``Added`` and
+/// ``Changed`` fetches return a pointer to the column's added or changed ticks instead, one ``u32`` per row, and every
+/// row of a mutably fetched column is marked as changed
 pub struct DynQueryColumnIter<'a, const N: usize> {
+    world: &'a World,
     comp_ids: [Option<EcsId>; N],
-    create_ptr: [fn(&Archetype, Option<EcsId>) -> (*mut u8, usize); N],
+    create_ptr: [CreatePtrFn; N],
     archetype_iter: crate::world::ArchetypeIter<'a, N>,
+    fetches: &'a [FetchType; N],
+    change_tick: u32,
 }
 
 impl<'a, const N: usize> Iterator for DynQueryColumnIter<'a, N> {
@@ -68,18 +177,52 @@ impl<'a, const N: usize> Iterator for DynQueryColumnIter<'a, N> {
         let archetype = self.archetype_iter.next()?;
         let mut ptrs = [PtrLen(0x0 as _, archetype.entities.len()); N];
         for n in 0..N {
-            let (ptr, _) = self.create_ptr[n](archetype, self.comp_ids[n]);
-            ptrs[n].0 = ptr;
+            let fetch = &self.fetches[n];
+            let ticks = fetch.create_ticks_ptr(archetype);
+            ptrs[n].0 = match fetch {
+                FetchType::Added(_) | FetchType::Changed(_) => ticks as *mut u8,
+                _ => self.create_ptr[n](self.world, archetype, self.comp_ids[n]).0,
+            };
+
+            fetch.update_all_ticks(ticks, archetype.entities.len(), self.change_tick);
         }
         Some(ptrs)
     }
 }
 
 pub struct DynQueryIter<'a, const N: usize> {
+    world: &'a World,
     comp_ids: [Option<EcsId>; N],
-    create_ptr: [fn(&Archetype, Option<EcsId>) -> (*mut u8, usize); N],
+    create_ptr: [CreatePtrFn; N],
     archetype_iter: crate::world::ArchetypeIter<'a, N>,
     intra_iter: IntraArchetypeIter<'a, N>,
+    fetches: &'a [FetchType; N],
+    last_run: u32,
+    change_tick: u32,
+}
+
+impl<'a, const N: usize> DynQueryIter<'a, N> {
+    /// Creates the iterator over the rows of the next matching archetype
+    fn next_archetype(&mut self) -> Option<IntraArchetypeIter<'a, N>> {
+        let archetype = self.archetype_iter.next()?;
+
+        let mut ptrs = [0x0 as _; N];
+        let mut offsets = [0; N];
+        for n in 0..N {
+            let (ptr, offset) = self.create_ptr[n](self.world, archetype, self.comp_ids[n]);
+            ptrs[n] = ptr;
+            offsets[n] = offset;
+        }
+
+        Some(IntraArchetypeIter::new(
+            archetype,
+            ptrs,
+            offsets,
+            self.fetches,
+            self.last_run,
+            self.change_tick,
+        ))
+    }
 }
 
 impl<'a, const N: usize> Iterator for DynQueryIter<'a, N> {
@@ -88,62 +231,209 @@ impl<'a, const N: usize> Iterator for DynQueryIter<'a, N> {
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             match self.intra_iter.next() {
-                None => {
-                    let archetype = self.archetype_iter.next()?;
-
-                    let mut ptrs = [0x0 as _; N];
-                    let mut offsets = [0; N];
-                    for n in 0..N {
-                        let (ptr, offset) = self.create_ptr[n](archetype, self.comp_ids[n]);
-                        ptrs[n] = ptr;
-                        offsets[n] = offset;
-                    }
-
-                    self.intra_iter =
-                        IntraArchetypeIter::new(archetype.entities.len(), ptrs, offsets);
-                }
+                None => self.intra_iter = self.next_archetype()?,
                 ptrs @ Some(_) => return ptrs,
             }
         }
     }
+
+    /// Folds one archetype at a time so ``for_each`` gets a loop per archetype that doesn't check ``uses_ticks``
+    /// for every row
+    fn fold<B, F>(mut self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let mut acc =
+            core::mem::replace(&mut self.intra_iter, IntraArchetypeIter::unit()).fold(init, &mut f);
+        while let Some(intra_iter) = self.next_archetype() {
+            acc = intra_iter.fold(acc, &mut f);
+        }
+        acc
+    }
 }
 
+type BitsetIter<'a> = (Iter<'a, usize>, fn(usize) -> usize);
+
+#[derive(Copy, Clone)]
 pub enum FetchType {
     EcsId,
     Mut(EcsId),
     Immut(EcsId),
+    /// Only matches archetypes with the component, does not fetch any data
+    With(EcsId),
+    /// Only matches archetypes without the component, does not fetch any data
+    Without(EcsId),
+    /// Fetches the component if it is present, the pointer is null for entities without the component
+    OptionalMut(EcsId),
+    /// Fetches the component if it is present, the pointer is null for entities without the component
+    OptionalImmut(EcsId),
+    /// Only matches entities that had the component added after the query's last run tick, does not fetch any data
+    Added(EcsId),
+    /// Only matches entities that had the component added or mutably accessed after the query's last run tick,
+    /// does not fetch any data
+    Changed(EcsId),
+    /// Fetches the resource with this id for every row, the pointer stays the same for every row of every archetype.
+    /// See ``World::insert_resource``
+    Resource(EcsId),
 }
 
 impl FetchType {
     pub(crate) fn get_id(&self) -> Option<EcsId> {
         Some(match self {
-            &Self::Mut(id) | &Self::Immut(id) => id,
+            &Self::Mut(id)
+            | &Self::Immut(id)
+            | &Self::With(id)
+            | &Self::Without(id)
+            | &Self::OptionalMut(id)
+            | &Self::OptionalImmut(id)
+            | &Self::Added(id)
+            | &Self::Changed(id)
+            | &Self::Resource(id) => id,
             Self::EcsId => return None,
         })
     }
 
-    pub(crate) fn make_create_ptr_fn(&self) -> fn(&Archetype, Option<EcsId>) -> (*mut u8, usize) {
+    pub(crate) fn make_create_ptr_fn(&self) -> CreatePtrFn {
         match self {
-            FetchType::EcsId => |archetype, _| {
+            FetchType::EcsId => |_, archetype, _| {
                 (
                     archetype.entities.as_ptr() as *mut EcsId as *mut u8,
                     core::mem::size_of::<EcsId>(),
                 )
             },
-            FetchType::Immut(_) => |archetype, id| {
+            FetchType::Immut(_) => |_, archetype, id| {
                 let storage_idx = archetype.comp_lookup[&id.unwrap()];
                 let storage = unsafe { &*archetype.component_storages[storage_idx].1.get() };
-                let size = storage.get_type_info().layout.size();
-                (unsafe { storage.as_immut_ptr() as *mut u8 }, size)
+                let size = storage.data.get_type_info().layout.size();
+                (unsafe { storage.data.as_immut_ptr() as *mut u8 }, size)
             },
-            FetchType::Mut(_) => |archetype, id| {
+            FetchType::Mut(_) => |_, archetype, id| {
                 let storage_idx = archetype.comp_lookup[&id.unwrap()];
                 let storage = unsafe { &mut *archetype.component_storages[storage_idx].1.get() };
-                let size = storage.get_type_info().layout.size();
-                (unsafe { storage.as_mut_ptr() }, size)
+                let size = storage.data.get_type_info().layout.size();
+                (unsafe { storage.data.as_mut_ptr() }, size)
+            },
+            FetchType::With(_)
+            | FetchType::Without(_)
+            | FetchType::Added(_)
+            | FetchType::Changed(_) => |_, _, _| (core::ptr::NonNull::dangling().as_ptr(), 0),
+            // An offset of 0 means the pointer is not advanced between rows
+            FetchType::Resource(_) => |world, _, id| match world.resource_ptr(id.unwrap()) {
+                Some((ptr, _)) => (ptr, 0),
+                None => (core::ptr::null_mut(), 0),
+            },
+            FetchType::OptionalImmut(_) => |_, archetype, id| {
+                let storage_idx = match archetype.comp_lookup.get(&id.unwrap()) {
+                    Some(&idx) => idx,
+                    None => return (core::ptr::null_mut(), 0),
+                };
+                let storage = unsafe { &*archetype.component_storages[storage_idx].1.get() };
+                let size = storage.data.get_type_info().layout.size();
+                (unsafe { storage.data.as_immut_ptr() as *mut u8 }, size)
+            },
+            FetchType::OptionalMut(_) => |_, archetype, id| {
+                let storage_idx = match archetype.comp_lookup.get(&id.unwrap()) {
+                    Some(&idx) => idx,
+                    None => return (core::ptr::null_mut(), 0),
+                };
+                let storage = unsafe { &mut *archetype.component_storages[storage_idx].1.get() };
+                let size = storage.data.get_type_info().layout.size();
+                (unsafe { storage.data.as_mut_ptr() }, size)
+            },
+        }
+    }
+
+    /// Returns the ticks of the column this fetch needs to read or update, the added ticks for ``Added`` and the
+    /// changed ticks otherwise, null if it doesn't use ticks
+    pub(crate) fn create_ticks_ptr(&self, archetype: &Archetype) -> *mut u32 {
+        match self {
+            &FetchType::Added(id) => archetype.get_ticks_ptr(id, true),
+            &FetchType::Mut(id) | &FetchType::OptionalMut(id) | &FetchType::Changed(id) => {
+                archetype.get_ticks_ptr(id, false)
+            }
+            _ => core::ptr::null_mut(),
+        }
+    }
+
+    /// Returns false if the row with the tick ``ticks`` should be skipped
+    pub(crate) fn filter_ticks(&self, ticks: *mut u32, last_run: u32) -> bool {
+        match self {
+            FetchType::Added(_) | FetchType::Changed(_) => unsafe { *ticks > last_run },
+            _ => true,
+        }
+    }
+
+    /// Marks mutably fetched components as changed
+    pub(crate) fn update_ticks(&self, ticks: *mut u32, change_tick: u32) {
+        if let FetchType::Mut(_) | FetchType::OptionalMut(_) = self {
+            if let Some(ticks) = unsafe { ticks.as_mut() } {
+                *ticks = change_tick;
+            }
+        }
+    }
+
+    /// Marks ``len`` mutably fetched components starting at ``ticks`` as changed
+    pub(crate) fn update_all_ticks(&self, ticks: *mut u32, len: usize, change_tick: u32) {
+        if let FetchType::Mut(_) | FetchType::OptionalMut(_) = self {
+            if !ticks.is_null() {
+                unsafe { core::slice::from_raw_parts_mut(ticks, len) }.fill(change_tick);
+            }
+        }
+    }
+
+    /// Returns true if this fetch locks the component ``id``
+    pub(crate) fn locks_component(&self, id: EcsId) -> bool {
+        match self {
+            &FetchType::Mut(fetch_id)
+            | &FetchType::Immut(fetch_id)
+            | &FetchType::OptionalMut(fetch_id)
+            | &FetchType::OptionalImmut(fetch_id)
+            | &FetchType::Resource(fetch_id) => fetch_id == id,
+            _ => false,
+        }
+    }
+
+    /// Creates the bitset iterator used to find archetypes matching this fetch, the returned
+    /// u32 is the length of the bitset if it should limit how many archetypes are iterated
+    fn make_archetype_iter<'a>(&self, world: &'a World) -> (BitsetIter<'a>, Option<u32>) {
+        let identity_fn: fn(_) -> _ = |x| x;
+        let neg_fn: fn(_) -> _ = |x: usize| !x;
+
+        match self {
+            FetchType::EcsId
+            | FetchType::OptionalMut(_)
+            | FetchType::OptionalImmut(_)
+            | FetchType::Resource(_) => ((world.entities_bitvec.data.iter(), identity_fn), None),
+            FetchType::Immut(id)
+            | FetchType::Mut(id)
+            | FetchType::With(id)
+            | FetchType::Added(id)
+            | FetchType::Changed(id) => match world.archetype_bitset.get_bitvec(*id) {
+                Some(bitvec) => ((bitvec.data.iter(), identity_fn), Some(bitvec.len as u32)),
+                None => ((world.entities_bitvec.data.iter(), identity_fn), Some(0)),
             },
+            FetchType::Without(id) => match world.archetype_bitset.get_bitvec(*id) {
+                Some(bitvec) => ((bitvec.data.iter(), neg_fn), None),
+                None => ((world.entities_bitvec.data.iter(), identity_fn), None),
+            },
+        }
+    }
+}
+
+/// Returns an error if a fetch borrows a component mutably and another fetch borrows the same component
+pub(crate) fn check_aliasing(fetches: &[FetchType]) -> Result<(), BorrowError> {
+    for (n, fetch) in fetches.iter().enumerate() {
+        if let &FetchType::Mut(id) | &FetchType::OptionalMut(id) = fetch {
+            let aliased = fetches
+                .iter()
+                .enumerate()
+                .any(|(other_n, other)| other_n != n && other.locks_component(id));
+            if aliased {
+                return Err(BorrowError::Aliased(id));
+            }
         }
     }
+    Ok(())
 }
 
 pub struct DynQuery<'a, const N: usize> {
@@ -151,31 +441,56 @@ pub struct DynQuery<'a, const N: usize> {
     _guards: [EitherGuard<'a>; N],
     fetches: [FetchType; N],
 
+    last_run: u32,
+    change_tick: u32,
+
     /// If set to true it means that some of the EcsId's used were not alive/existing
     incomplete: bool,
 }
 
 impl<'a, const N: usize> DynQuery<'a, N> {
     pub(crate) fn new(world: &'a World, fetches: [FetchType; N]) -> Self {
+        check_aliasing(&fetches).unwrap_or_else(|err| panic!("{}", err));
         let mut incomplete = false;
 
         const NONE: EitherGuard = EitherGuard::None;
         let mut guards = [NONE; N];
 
         for (fetch, guard) in fetches.iter().zip(guards.iter_mut()) {
-            let ecs_id = match fetch {
-                FetchType::EcsId => continue,
-                FetchType::Immut(id) | FetchType::Mut(id) => id,
+            let (ecs_id, optional) = match fetch {
+                FetchType::EcsId | FetchType::Without(_) => continue,
+                // Wildcard pairs are never components so there is nothing to lock
+                FetchType::With(id) if id.is_wildcard() => continue,
+                FetchType::Immut(id) | FetchType::Mut(id) | FetchType::With(id) => (id, false),
+                FetchType::OptionalImmut(id) | FetchType::OptionalMut(id) => (id, true),
+                FetchType::Resource(id) => {
+                    if world.resource_ptr(*id).is_none() {
+                        incomplete = true;
+                        continue;
+                    }
+                    (id, false)
+                }
+                // The ticks are already locked if another fetch borrows the same component
+                FetchType::Added(id) | FetchType::Changed(id) => {
+                    if fetches.iter().any(|fetch| fetch.locks_component(*id)) {
+                        continue;
+                    }
+                    (id, false)
+                }
             };
 
-            if let Some(&idx) = world.lock_lookup.get(ecs_id) {
-                let lock = &world.locks[idx];
-                match fetch {
-                    FetchType::Mut(_) => *guard = EitherGuard::Write(lock.write().unwrap()),
-                    FetchType::Immut(_) => *guard = EitherGuard::Read(lock.read().unwrap()),
-                    _ => (),
-                }
-            } else {
+            if world.lock_lookup.contains_key(ecs_id) {
+                let lock = match fetch {
+                    FetchType::Mut(_) | FetchType::OptionalMut(_) => world.try_lock(*ecs_id, true),
+                    FetchType::Immut(_)
+                    | FetchType::OptionalImmut(_)
+                    | FetchType::Added(_)
+                    | FetchType::Changed(_)
+                    | FetchType::Resource(_) => world.try_lock(*ecs_id, false),
+                    _ => Ok(EitherGuard::None),
+                };
+                *guard = lock.unwrap_or_else(|err| panic!("{}", err));
+            } else if !optional {
                 incomplete = true;
             }
         }
@@ -184,20 +499,27 @@ impl<'a, const N: usize> DynQuery<'a, N> {
             world,
             _guards: guards,
             fetches,
+            last_run: 0,
+            change_tick: world.change_tick(),
             incomplete,
         }
     }
 
+    /// Sets the tick that ``Added`` and ``Changed`` fetches compare against, only components added/changed after
+    /// this tick will match. Defaults to 0 which matches every component
+    pub fn since(mut self, last_run: u32) -> Self {
+        self.last_run = last_run;
+        self
+    }
+
     pub fn column_iter(&mut self) -> DynQueryColumnIter<'_, N> {
         const NONE_ID: Option<EcsId> = None;
         let mut ecs_ids = [NONE_ID; N];
         for (fetch, ecs_id) in self.fetches.iter().zip(ecs_ids.iter_mut()) {
-            if let FetchType::Immut(id) | FetchType::Mut(id) = fetch {
-                *ecs_id = Some(*id);
-            }
+            *ecs_id = fetch.get_id();
         }
 
-        const DEFAULT_FN: fn(&Archetype, Option<EcsId>) -> (*mut u8, usize) = |_, _| panic!();
+        const DEFAULT_FN: CreatePtrFn = |_, _, _| panic!();
         let mut create_ptr = [DEFAULT_FN; N];
         for (fetch, func) in self.fetches.iter().zip(create_ptr.iter_mut()) {
             *func = fetch.make_create_ptr_fn();
@@ -216,21 +538,16 @@ impl<'a, const N: usize> DynQuery<'a, N> {
 
             self.world.query_archetypes(iters, bit_length)
         } else {
-            let identity_fn: fn(_) -> _ = |x| x;
-
             let mut bit_length = self.world.entities_bitvec.len as u32;
-            let boxed_iters = ecs_ids
+            let boxed_iters = self
+                .fetches
                 .iter()
-                .map(|id| match id {
-                    None => (self.world.entities_bitvec.data.iter(), identity_fn),
-                    Some(id) => {
-                        let bitvec = self.world.archetype_bitset.get_bitvec(*id).unwrap();
-                        if { bitvec.len as u32 } < bit_length {
-                            bit_length = bitvec.len as u32;
-                        }
-
-                        (bitvec.data.iter(), identity_fn)
+                .map(|fetch| {
+                    let (iter, len) = fetch.make_archetype_iter(self.world);
+                    if let Some(len) = len {
+                        bit_length = u32::min(bit_length, len);
                     }
+                    iter
                 })
                 .collect::<Box<[_]>>();
             use std::convert::TryInto;
@@ -241,9 +558,12 @@ impl<'a, const N: usize> DynQuery<'a, N> {
         };
 
         DynQueryColumnIter {
+            world: self.world,
             comp_ids: ecs_ids,
             create_ptr,
             archetype_iter,
+            fetches: &self.fetches,
+            change_tick: self.change_tick,
         }
     }
 
@@ -251,12 +571,10 @@ impl<'a, const N: usize> DynQuery<'a, N> {
         const NONE_ID: Option<EcsId> = None;
         let mut ecs_ids = [NONE_ID; N];
         for (fetch, ecs_id) in self.fetches.iter().zip(ecs_ids.iter_mut()) {
-            if let FetchType::Immut(id) | FetchType::Mut(id) = fetch {
-                *ecs_id = Some(*id);
-            }
+            *ecs_id = fetch.get_id();
         }
 
-        const DEFAULT_FN: fn(&Archetype, Option<EcsId>) -> (*mut u8, usize) = |_, _| panic!();
+        const DEFAULT_FN: CreatePtrFn = |_, _, _| panic!();
         let mut create_ptr = [DEFAULT_FN; N];
         for (fetch, func) in self.fetches.iter().zip(create_ptr.iter_mut()) {
             *func = fetch.make_create_ptr_fn();
@@ -275,21 +593,16 @@ impl<'a, const N: usize> DynQuery<'a, N> {
 
             self.world.query_archetypes(iters, bit_length)
         } else {
-            let identity_fn: fn(_) -> _ = |x| x;
-
             let mut bit_length = self.world.entities_bitvec.len as u32;
-            let boxed_iters = ecs_ids
+            let boxed_iters = self
+                .fetches
                 .iter()
-                .map(|id| match id {
-                    None => (self.world.entities_bitvec.data.iter(), identity_fn),
-                    Some(id) => {
-                        let bitvec = self.world.archetype_bitset.get_bitvec(*id).unwrap();
-                        if { bitvec.len as u32 } < bit_length {
-                            bit_length = bitvec.len as u32;
-                        }
-
-                        (bitvec.data.iter(), identity_fn)
+                .map(|fetch| {
+                    let (iter, len) = fetch.make_archetype_iter(self.world);
+                    if let Some(len) = len {
+                        bit_length = u32::min(bit_length, len);
                     }
+                    iter
                 })
                 .collect::<Box<[_]>>();
             use std::convert::TryInto;
@@ -300,10 +613,14 @@ impl<'a, const N: usize> DynQuery<'a, N> {
         };
 
         DynQueryIter {
+            world: self.world,
             comp_ids: ecs_ids,
             create_ptr,
             archetype_iter,
             intra_iter: IntraArchetypeIter::unit(),
+            fetches: &self.fetches,
+            last_run: self.last_run,
+            change_tick: self.change_tick,
         }
     }
 }
@@ -1,3 +1,17 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Set in the generation of pair ids, the rest of a pair's generation is the index of its relation
+const PAIR_FLAG: u32 = 1 << 31;
+
+/// Entity indices stay below ``PAIR_FLAG`` so that every entity can be the relation of a pair and no entity has the
+/// index of ``EcsId::WILDCARD``
+pub(crate) const MAX_INDEX: usize = PAIR_FLAG as usize - 1;
+
+/// Generations wrap before reaching ``PAIR_FLAG`` so that entities are never mistaken for pairs
+fn next_generation(generation: u32) -> u32 {
+    generation.wrapping_add(1) & !PAIR_FLAG
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct EcsIdGen(u32);
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -66,6 +80,54 @@ impl EcsId {
         Self(EcsIdGen(generation), EcsIdIndex(index))
     }
 
+    /// Used as the target of a pair to match every target of a relation, see ``EcsId::pair``
+    pub const WILDCARD: EcsId = EcsId(EcsIdGen(0), EcsIdIndex(u32::MAX));
+
+    /// The id of the pair ``(relation, target)``, a component that can be added to entities like any other component.
+    /// Pairs take on the ``ComponentMeta`` of their relation and are removed from every entity when either their
+    /// relation or target is despawned.
+    ///
+    /// The generations of ``relation`` and ``target`` are not part of the id, only their indices are. A pair with
+    /// ``EcsId::WILDCARD`` as the target can be used with ``FetchType::With`` and ``FetchType::Without`` to match
+    /// entities with any target of the relation.
+    ///
+    /// Panics if either half is a pair or has an index above the limit that ``Entities::spawn`` keeps entities under
+    pub fn pair(relation: EcsId, target: EcsId) -> EcsId {
+        assert!(
+            !relation.is_pair() && !target.is_pair(),
+            "Attempted to create a pair out of a pair"
+        );
+        assert!(
+            relation != EcsId::WILDCARD,
+            "Attempted to create a pair with a wildcard relation"
+        );
+        assert!(
+            relation.uindex() <= MAX_INDEX
+                && (target == EcsId::WILDCARD || target.uindex() <= MAX_INDEX),
+            "Attempted to create a pair out of an id with an index above the entity index limit"
+        );
+        EcsId::new(target.index().0, PAIR_FLAG | relation.index().0)
+    }
+
+    pub fn is_pair(&self) -> bool {
+        self.generation().0 & PAIR_FLAG != 0
+    }
+
+    /// The index of the relation of a pair, the index of its target is ``EcsId::uindex``
+    pub(crate) fn relation_index(&self) -> usize {
+        (self.generation().0 & !PAIR_FLAG) as usize
+    }
+
+    /// Whether this is a pair with ``EcsId::WILDCARD`` as its target
+    pub(crate) fn is_wildcard(&self) -> bool {
+        self.is_pair() && self.index() == EcsId::WILDCARD.index()
+    }
+
+    /// The pair with the same relation as this pair and ``EcsId::WILDCARD`` as its target
+    pub(crate) fn wildcard(&self) -> EcsId {
+        EcsId(self.generation(), EcsId::WILDCARD.index())
+    }
+
     pub fn as_u64(&self) -> u64 {
         let gen = self.generation().0;
         let gen = { gen as u64 } << 32;
@@ -76,11 +138,29 @@ impl EcsId {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for EcsId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.as_u64())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for EcsId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = <u64 as serde::Deserialize>::deserialize(deserializer)?;
+        Ok(EcsId::new(id as u32, (id >> 32) as u32))
+    }
+}
+
 pub struct Entities {
     /// the bool is whether the entity is alive
     /// the u32 is the generation of the entity
     pub(crate) generations: Vec<(bool, u32)>,
     pub(crate) despawned: Vec<usize>,
+    /// Number of ids reserved since the last flush, reservations take ids from the end of ``despawned`` in the same
+    /// order that ``Entities::spawn`` would and then continue on past the end of ``generations``
+    pub(crate) reserved: AtomicUsize,
 }
 
 impl Entities {
@@ -88,34 +168,86 @@ impl Entities {
         Self {
             generations: Vec::with_capacity(4096),
             despawned: Vec::with_capacity(512),
+            reserved: AtomicUsize::new(0),
         }
     }
 
+    /// Reserves an id that can be used before the entity is spawned, ``Entities::flush_reserved`` must be called before
+    /// spawning or despawning again
+    pub fn reserve(&self) -> EcsId {
+        let n = self.reserved.fetch_add(1, Ordering::Relaxed);
+        self.nth_reserved(n)
+    }
+
+    /// Reserves ``count`` ids at once, see ``Entities::reserve``
+    pub fn reserve_many(&self, count: usize) -> impl Iterator<Item = EcsId> + '_ {
+        let start = self.reserved.fetch_add(count, Ordering::Relaxed);
+        (start..start + count).map(move |n| self.nth_reserved(n))
+    }
+
+    fn nth_reserved(&self, n: usize) -> EcsId {
+        let idx = match n < self.despawned.len() {
+            true => self.despawned[self.despawned.len() - 1 - n],
+            false => self.generations.len() + (n - self.despawned.len()),
+        };
+
+        assert!(
+            idx <= MAX_INDEX,
+            "Attempted to reserve more entity ids than an EcsId can index"
+        );
+
+        let gen = match self.generations.get(idx) {
+            Some(&(_, gen)) => next_generation(gen),
+            None => 0,
+        };
+        EcsId::new(idx as u32, gen)
+    }
+
+    /// Marks all reserved ids as alive, returns the ids that were reserved
+    pub fn flush_reserved(&mut self) -> Vec<EcsId> {
+        let reserved = std::mem::replace(self.reserved.get_mut(), 0);
+        let mut flushed = Vec::with_capacity(reserved);
+        for _ in 0..reserved {
+            flushed.push(self.spawn());
+        }
+        flushed
+    }
+
     pub fn spawn(&mut self) -> EcsId {
+        assert!(
+            *self.reserved.get_mut() == 0,
+            "Reserved entities must be flushed before spawning"
+        );
+
         let idx = match self.despawned.pop() {
             Some(idx) => {
                 let (alive, gen) = &mut self.generations[idx];
                 assert!(*alive == false);
-                *gen = gen.wrapping_add(1);
+                *gen = next_generation(*gen);
                 *alive = true;
                 idx
             }
             None => {
+                assert!(
+                    self.generations.len() <= MAX_INDEX,
+                    "Attempted to spawn more entities than an EcsId can index"
+                );
                 self.generations.push((true, 0));
                 self.generations.len() - 1
             }
         };
 
-        if idx > u32::MAX as usize {
-            todo!("Handle running out of entity ids");
-        }
-
         let &mut (_, gen) = &mut self.generations[idx];
         EcsId::new(idx as u32, gen)
     }
 
     /// Returns true if entity was despawned
     pub fn despawn(&mut self, to_despawn: EcsId) -> bool {
+        assert!(
+            *self.reserved.get_mut() == 0,
+            "Reserved entities must be flushed before despawning"
+        );
+
         if self.is_alive(to_despawn) {
             let (alive, _) = &mut self.generations[to_despawn.uindex()];
             *alive = false;
@@ -126,12 +258,39 @@ impl Entities {
         }
     }
 
+    /// Reserved ids are considered alive even before they are flushed
     pub fn is_alive(&self, entity: EcsId) -> bool {
-        let &(alive, stored_generation) = self
-            .generations
-            .get(entity.uindex())
-            .unwrap_or_else(|| panic!("could not get generation for {}", entity));
         let generation = entity.generation().0;
-        alive && generation == stored_generation
+        match self.generations.get(entity.uindex()) {
+            Some(&(true, stored_generation)) => generation == stored_generation,
+            Some(&(false, stored_generation)) => {
+                generation == next_generation(stored_generation)
+                    && self.is_reserved_index(entity.uindex())
+            }
+            None if self.is_reserved_index(entity.uindex()) => generation == 0,
+            None => panic!("could not get generation for {}", entity),
+        }
+    }
+
+    /// The id of the entity at ``idx`` if it is alive, reserved ids are not considered alive
+    pub(crate) fn alive_at(&self, idx: usize) -> Option<EcsId> {
+        match self.generations.get(idx) {
+            Some(&(true, generation)) => Some(EcsId::new(idx as u32, generation)),
+            _ => None,
+        }
+    }
+
+    fn is_reserved_index(&self, idx: usize) -> bool {
+        let reserved = self.reserved.load(Ordering::Relaxed);
+        if reserved == 0 {
+            return false;
+        }
+
+        if idx >= self.generations.len() {
+            return idx - self.generations.len() < reserved.saturating_sub(self.despawned.len());
+        }
+
+        let reused = usize::min(reserved, self.despawned.len());
+        self.despawned[self.despawned.len() - reused..].contains(&idx)
     }
 }
@@ -6,10 +6,11 @@ use std::{
 };
 
 use crate::{
-    world::{AddRemoveCache, Archetype, ComponentMeta},
-    EcsId, World,
+    utils::drop_unaligned,
+    world::{Archetype, ArchetypeEdges, Column, ComponentMeta, ComponentTicks},
+    EcsError, EcsId, World,
 };
-use untyped_vec::{TypeInfo, UntypedVec};
+use untyped_vec::TypeInfo;
 
 pub struct EntityBuilder<'a> {
     data: NonNull<u8>,
@@ -21,6 +22,8 @@ pub struct EntityBuilder<'a> {
     component_meta: ComponentMeta,
 
     num_components: usize,
+    /// The first error encountered while adding components, returned by ``EntityBuilder::try_build``
+    error: Option<EcsError>,
 
     world: &'a mut World,
 }
@@ -75,6 +78,7 @@ impl<'a> EntityBuilder<'a> {
             entity,
             world,
             num_components: 0,
+            error: None,
         }
     }
 
@@ -94,6 +98,7 @@ impl<'a> EntityBuilder<'a> {
             component_meta,
 
             num_components: 0,
+            error: None,
 
             world,
         }
@@ -129,6 +134,7 @@ impl<'a> EntityBuilder<'a> {
             component_meta,
 
             num_components: 0,
+            error: None,
 
             world,
         }
@@ -162,16 +168,19 @@ impl<'a> EntityBuilder<'a> {
 
     /// Adds an entity as a dataless component
     ///
-    /// This method will panic if a component with the ID of component_id expects data. Entities by default expect no data.
+    /// If a component with the ID of component_id expects data ``EcsError::NotUnitComponent`` is returned from
+    /// ``EntityBuilder::try_build`` and ``EntityBuilder::build`` will panic. Entities by default expect no data.
     #[must_use]
     pub fn with_dynamic(mut self, component_id: EcsId) -> Self {
-        assert!(
-            self.world
-                .get_entity_meta(component_id)
-                .unwrap()
-                .component_meta
-                .is_unit
-        );
+        let error = match self.world.get_component_meta(component_id) {
+            Some(meta) if meta.is_unit => None,
+            Some(_) => Some(EcsError::NotUnitComponent),
+            None => Some(EcsError::NoSuchEntity),
+        };
+        if let Some(error) = error {
+            self.error.get_or_insert(error);
+            return self;
+        }
 
         self.comp_ids.push(component_id);
         self.num_components += 1;
@@ -192,9 +201,8 @@ impl<'a> EntityBuilder<'a> {
         self.comp_ids.push(component_id);
         let component_size = self
             .world
-            .get_entity_meta(component_id)
+            .get_component_meta(component_id)
             .expect("Dead entity may not be used as a component")
-            .component_meta
             .layout
             .size();
 
@@ -224,21 +232,41 @@ impl<'a> EntityBuilder<'a> {
         unsafe { self.with_dynamic_with_data(&mut component as *mut _ as *mut _, component_id) }
     }
 
+    /// Panicking version of ``EntityBuilder::try_build``
     pub fn build(&mut self) -> EcsId {
-        use crate::world::{ArchIndex, EntityMeta, InstanceMeta};
+        self.try_build().unwrap()
+    }
+
+    /// Moves the entity and its components into the world, if any of the components could not be added the
+    /// entity is despawned and the components are dropped
+    pub fn try_build(&mut self) -> Result<EcsId, EcsError> {
+        if let Some(error) = self.error.take() {
+            let mut data_ptr = self.data.as_ptr();
+            for comp_id in self.comp_ids.drain(..) {
+                let component_meta = self.world.get_component_meta(comp_id).unwrap();
+                // Safe because every component was moved into the buffer with its own layout and is never used again
+                unsafe {
+                    drop_unaligned(data_ptr, component_meta);
+                    data_ptr = data_ptr.add(component_meta.layout.size());
+                }
+            }
+            self.len = 0;
+            self.num_components = 0;
+            // The entity has no entity meta yet so it can't go through World::despawn
+            self.world.entities.despawn(self.entity);
+            return Err(error);
+        }
+
+        use crate::world::{EntityMeta, InstanceMeta};
         if let Some(arch_index) = self.world.find_archetype_dynamic(&self.comp_ids) {
             self.world.archetypes[arch_index.0]
                 .entities
                 .push(self.entity);
 
+            let ticks = ComponentTicks::new(self.world.change_tick());
             let mut data_ptr = self.data.as_ptr();
             for &comp_id in &self.comp_ids {
-                let component_meta = self
-                    .world
-                    .get_entity_meta(comp_id)
-                    .unwrap()
-                    .component_meta
-                    .clone();
+                let component_meta = self.world.get_component_meta(comp_id).unwrap().clone();
 
                 let archetype = &mut self.world.archetypes[arch_index.0];
                 let comp_storage_index = archetype.comp_lookup[&comp_id];
@@ -246,7 +274,7 @@ impl<'a> EntityBuilder<'a> {
                     archetype.component_storages[comp_storage_index]
                         .1
                         .get_mut()
-                        .push_raw(data_ptr.cast());
+                        .push_raw(data_ptr.cast(), ticks);
                     data_ptr = data_ptr.offset(component_meta.layout.size() as isize);
                 }
 
@@ -278,16 +306,7 @@ impl<'a> EntityBuilder<'a> {
             }
 
             let archetype = self.create_archetype();
-
-            for id in archetype.comp_ids.iter() {
-                self.world
-                    .archetype_bitset
-                    .set_bit(*id, self.world.archetypes.len(), true);
-            }
-            self.world.entities_bitvec.push_bit(true);
-
-            self.world.archetypes.push(archetype);
-            let (archetype_idx, entity_idx) = (ArchIndex(self.world.archetypes.len() - 1), 0);
+            let (archetype_idx, entity_idx) = (self.world.push_archetype(archetype), 0);
 
             let entity_meta = EntityMeta {
                 instance_meta: InstanceMeta {
@@ -299,24 +318,22 @@ impl<'a> EntityBuilder<'a> {
             self.world.set_entity_meta(self.entity, entity_meta);
         }
 
-        self.entity
+        Ok(self.entity)
     }
 
     /// Creates an archetype and moves the built entity into it
     fn create_archetype(&mut self) -> Archetype {
         let mut component_storages = Vec::with_capacity(self.num_components);
 
+        let ticks = ComponentTicks::new(self.world.change_tick());
         let mut data_ptr = self.data.as_ptr();
         for &comp_id in &self.comp_ids {
-            let component_meta = &self.world.get_entity_meta(comp_id).unwrap().component_meta;
-            let mut untyped_vec = unsafe {
-                UntypedVec::new_from_raw(TypeInfo::new(
-                    component_meta.layout,
-                    component_meta.drop_fn,
-                ))
+            let component_meta = self.world.get_component_meta(comp_id).unwrap();
+            let mut column = unsafe {
+                Column::new(TypeInfo::new(component_meta.layout, component_meta.drop_fn))
             };
-            unsafe { untyped_vec.push_raw(data_ptr.cast()) };
-            component_storages.push((comp_id, std::cell::UnsafeCell::new(untyped_vec)));
+            unsafe { column.push_raw(data_ptr.cast(), ticks) };
+            component_storages.push((comp_id, std::cell::UnsafeCell::new(column)));
 
             data_ptr = unsafe { data_ptr.offset(component_meta.layout.size() as isize) };
         }
@@ -348,7 +365,8 @@ impl<'a> EntityBuilder<'a> {
             comp_lookup: lookup,
             comp_ids: std::mem::replace(&mut self.comp_ids, Vec::new()),
             component_storages,
-            add_remove_cache: AddRemoveCache::new(),
+            add_edges: ArchetypeEdges::new(),
+            remove_edges: ArchetypeEdges::new(),
         }
     }
 }
@@ -0,0 +1,122 @@
+use crate::EcsId;
+use std::fmt;
+
+/// Errors returned by the non-panicking ``World`` methods such as ``World::try_add`` and ``World::try_remove``
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EcsError {
+    /// The entity, or the entity being used as a component, is not alive
+    NoSuchEntity,
+    /// The entity already has the component being added
+    AlreadyPresent,
+    /// The entity does not have the component being removed
+    Missing,
+    /// A component that expects data was added without any
+    NotUnitComponent,
+}
+
+impl fmt::Display for EcsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EcsError::NoSuchEntity => write!(f, "Entity is not alive"),
+            EcsError::AlreadyPresent => write!(f, "Entity already has the component"),
+            EcsError::Missing => write!(f, "Entity does not have the component"),
+            EcsError::NotUnitComponent => write!(f, "Component expects data but none was given"),
+        }
+    }
+}
+
+impl std::error::Error for EcsError {}
+
+/// Returned by ``World::try_query`` when a component can't be borrowed, the panicking query methods fail with this
+/// error instead of blocking
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BorrowError {
+    /// The query borrows the component mutably and borrows it again with another param
+    Aliased(EcsId),
+    /// The component is already borrowed by a query, ``Res`` or ``ResMut`` that is still alive and one of the two
+    /// borrows is mutable
+    Conflict(EcsId),
+}
+
+impl BorrowError {
+    /// The id of the component that couldn't be borrowed
+    pub fn component(&self) -> EcsId {
+        match self {
+            &BorrowError::Aliased(id) | &BorrowError::Conflict(id) => id,
+        }
+    }
+}
+
+impl fmt::Display for BorrowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BorrowError::Aliased(id) => {
+                write!(
+                    f,
+                    "Component {} is borrowed mutably and borrowed again by the same query",
+                    id
+                )
+            }
+            BorrowError::Conflict(id) => write!(f, "Component {} is already borrowed", id),
+        }
+    }
+}
+
+impl std::error::Error for BorrowError {}
+
+/// Returned by ``World::try_clone_entity`` and ``World::try_clone``
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CloneError {
+    /// The entity being cloned is not alive
+    NoSuchEntity,
+    /// The component was not created with ``ComponentMeta::from_generic_clone`` or registered with
+    /// ``World::register_clone``
+    NotCloneable(EcsId),
+    /// A component of the world being cloned is borrowed mutably by a query
+    Borrow(BorrowError),
+}
+
+impl fmt::Display for CloneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CloneError::NoSuchEntity => write!(f, "Entity is not alive"),
+            CloneError::NotCloneable(id) => write!(f, "Component {} can't be cloned", id),
+            CloneError::Borrow(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for CloneError {}
+
+/// Returned by ``World::save`` and ``World::load``
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum SerdeError {
+    /// The saved world could not be written or read, or it is malformed
+    Format(bincode::Error),
+    /// A component that holds data has not been registered with the ``ComponentRegistry``
+    Unregistered(EcsId),
+    /// The saved world has a component with a name that has not been registered with the ``ComponentRegistry``
+    UnknownName(String),
+}
+
+#[cfg(feature = "serde")]
+impl From<bincode::Error> for SerdeError {
+    fn from(err: bincode::Error) -> Self {
+        SerdeError::Format(err)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for SerdeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerdeError::Format(err) => write!(f, "{}", err),
+            SerdeError::Unregistered(id) => write!(f, "Component {} is not registered", id),
+            SerdeError::UnknownName(name) => write!(f, "No component is registered as {}", name),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for SerdeError {}
@@ -0,0 +1,237 @@
+use crate::{utils::EitherGuard, EcsId, EntityMap, MapEntities, World};
+use std::any::TypeId;
+use std::collections::VecDeque;
+use std::ops::Deref;
+
+/// The parent of an entity, added and removed by ``World::set_parent`` and ``World::remove_parent``
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Parent(EcsId);
+
+impl Parent {
+    pub fn get(&self) -> EcsId {
+        self.0
+    }
+}
+
+/// The children of an entity in the order they were given their parent, an entity without children has no
+/// ``Children`` component
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Children(Vec<EcsId>);
+
+impl MapEntities for Parent {
+    fn map_entities(&mut self, entity_map: &EntityMap) {
+        self.0 = entity_map.get(self.0).unwrap();
+    }
+}
+
+impl MapEntities for Children {
+    fn map_entities(&mut self, entity_map: &EntityMap) {
+        for child in self.0.iter_mut() {
+            *child = entity_map.get(*child).unwrap();
+        }
+    }
+}
+
+impl Deref for Children {
+    type Target = [EcsId];
+
+    fn deref(&self) -> &[EcsId] {
+        &self.0
+    }
+}
+
+impl World {
+    /// Makes ``parent`` the parent of ``child``, removing ``child`` from the children of its previous parent
+    ///
+    /// This panics if either entity is dead or if ``parent`` is ``child`` or one of its descendants
+    pub fn set_parent(&mut self, child: EcsId, parent: EcsId) {
+        assert!(
+            self.is_alive(child) && self.is_alive(parent),
+            "Attempted to set the parent of a dead entity or to a dead entity"
+        );
+        assert!(
+            child != parent
+                && !self
+                    .descendants_depth_first(child)
+                    .any(|entity| entity == parent),
+            "Attempted to make an entity a descendant of itself"
+        );
+
+        // Hierarchies stay intact when the world is merged into another one
+        if !self.type_id_to_ecs_id.contains_key(&TypeId::of::<Parent>()) {
+            self.register_map_entities::<Parent>();
+            self.register_map_entities::<Children>();
+        }
+
+        self.remove_parent(child);
+        match self.get_mut::<Children>(parent) {
+            Some(children) => children.0.push(child),
+            None => self.add_component(parent, Children(vec![child])),
+        }
+        self.add_component(child, Parent(parent));
+    }
+
+    /// Removes ``child`` from the children of its parent and returns the parent, None if it had no parent
+    pub fn remove_parent(&mut self, child: EcsId) -> Option<EcsId> {
+        let Parent(parent) = self.take::<Parent>(child)?;
+        // A parent without ``Children`` already has nothing to detach ``child`` from
+        if let Some(children) = self.get_mut::<Children>(parent) {
+            children.0.retain(|&entity| entity != child);
+            if children.0.is_empty() {
+                self.remove_component::<Children>(parent);
+            }
+        }
+        Some(parent)
+    }
+
+    /// Adds ``clone`` to the children of the parent it was cloned with, called by ``World::clone_entity``
+    pub(crate) fn attach_clone_to_parent(&mut self, clone: EcsId) {
+        if let Some(&Parent(parent)) = self.get::<Parent>(clone) {
+            match self.get_mut::<Children>(parent) {
+                Some(children) => children.0.push(clone),
+                None => self.add_component(parent, Children(vec![clone])),
+            }
+        }
+    }
+
+    /// Clones the descendants of ``entity`` as the descendants of ``clone``, called by ``World::clone_entity`` once
+    /// every descendant is known to be cloneable
+    pub(crate) fn clone_children(&mut self, entity: EcsId, clone: EcsId) {
+        let children = match self.get::<Children>(entity) {
+            Some(Children(children)) => children.clone(),
+            None => return,
+        };
+
+        let mut cloned_children = Vec::with_capacity(children.len());
+        for child in children {
+            let cloned_child = self.clone_single_entity(child).unwrap();
+            *self.get_mut::<Parent>(cloned_child).unwrap() = Parent(clone);
+            self.clone_children(child, cloned_child);
+            cloned_children.push(cloned_child);
+        }
+        self.get_mut::<Children>(clone).unwrap().0 = cloned_children;
+    }
+
+    /// Despawns ``entity`` and all of its descendants, returns false if ``entity`` was already dead
+    pub fn despawn_recursive(&mut self, entity: EcsId) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+
+        let descendants = self.descendants_depth_first(entity).collect::<Vec<_>>();
+        // Leaves first so that no entity is orphaned only to be despawned right after
+        for descendant in descendants.into_iter().rev() {
+            self.despawn(descendant);
+        }
+        self.despawn(entity)
+    }
+
+    /// Removes ``entity`` from its parent's children and orphans its own children, called by ``World::despawn``
+    pub(crate) fn detach_from_hierarchy(&mut self, entity: EcsId) {
+        self.remove_parent(entity);
+        if let Some(Children(children)) = self.take::<Children>(entity) {
+            // A child's ``Parent`` may have been removed or replaced without going through ``World::remove_parent``
+            for child in children {
+                if self.get::<Parent>(child) == Some(&Parent(entity)) {
+                    self.take::<Parent>(child);
+                }
+            }
+        }
+    }
+
+    /// Iterates the descendants of ``entity`` depth first, each entity's children are visited in order
+    ///
+    /// ``Children`` stays borrowed until the iterator is dropped, this panics if a query has it borrowed mutably
+    pub fn descendants_depth_first(&self, entity: EcsId) -> DescendantsDepthFirst<'_> {
+        let (guard, children_id) = self.lock_children();
+        let mut iter = DescendantsDepthFirst {
+            world: self,
+            _guard: guard,
+            children_id,
+            stack: Vec::new(),
+        };
+        iter.push_children(entity);
+        iter
+    }
+
+    /// Iterates the descendants of ``entity`` breadth first, each entity's children are visited in order
+    ///
+    /// ``Children`` stays borrowed until the iterator is dropped, this panics if a query has it borrowed mutably
+    pub fn descendants_breadth_first(&self, entity: EcsId) -> DescendantsBreadthFirst<'_> {
+        let (guard, children_id) = self.lock_children();
+        let mut iter = DescendantsBreadthFirst {
+            world: self,
+            _guard: guard,
+            children_id,
+            queue: VecDeque::new(),
+        };
+        iter.queue.extend(children_of(self, children_id, entity));
+        iter
+    }
+
+    fn lock_children(&self) -> (EitherGuard<'_>, Option<EcsId>) {
+        match self.type_id_to_ecs_id.get(&TypeId::of::<Children>()) {
+            Some(&children_id) => {
+                let guard = self
+                    .try_lock(children_id, false)
+                    .unwrap_or_else(|err| panic!("{}", err));
+                (guard, Some(children_id))
+            }
+            None => (EitherGuard::None, None),
+        }
+    }
+}
+
+/// The children of ``entity``, the caller must hold a read lock on ``Children``
+fn children_of(world: &World, children_id: Option<EcsId>, entity: EcsId) -> &[EcsId] {
+    match children_id.and_then(|id| world.get_component_dynamic(entity, id)) {
+        Some(ptr) => unsafe { &*(ptr as *const Children) },
+        None => &[],
+    }
+}
+
+/// Returned by ``World::descendants_depth_first``
+pub struct DescendantsDepthFirst<'a> {
+    world: &'a World,
+    _guard: EitherGuard<'a>,
+    children_id: Option<EcsId>,
+    stack: Vec<EcsId>,
+}
+
+impl<'a> DescendantsDepthFirst<'a> {
+    fn push_children(&mut self, entity: EcsId) {
+        let children = children_of(self.world, self.children_id, entity);
+        self.stack.extend(children.iter().rev());
+    }
+}
+
+impl<'a> Iterator for DescendantsDepthFirst<'a> {
+    type Item = EcsId;
+
+    fn next(&mut self) -> Option<EcsId> {
+        let entity = self.stack.pop()?;
+        self.push_children(entity);
+        Some(entity)
+    }
+}
+
+/// Returned by ``World::descendants_breadth_first``
+pub struct DescendantsBreadthFirst<'a> {
+    world: &'a World,
+    _guard: EitherGuard<'a>,
+    children_id: Option<EcsId>,
+    queue: VecDeque<EcsId>,
+}
+
+impl<'a> Iterator for DescendantsBreadthFirst<'a> {
+    type Item = EcsId;
+
+    fn next(&mut self) -> Option<EcsId> {
+        let entity = self.queue.pop_front()?;
+        self.queue
+            .extend(children_of(self.world, self.children_id, entity));
+        Some(entity)
+    }
+}
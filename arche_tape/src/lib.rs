@@ -16,32 +16,78 @@ macro_rules! spawn {
 
 mod bitset_iterator;
 
+pub mod bundle;
+pub mod clone;
+pub mod command_buffer;
 pub mod entities;
 pub mod entity_builder;
+pub mod error;
+#[cfg(feature = "hierarchy")]
+pub mod hierarchy;
+pub mod merge;
+pub mod resource;
+pub mod schedule;
+#[cfg(feature = "serde")]
+pub mod serialize;
 pub mod world;
 
 pub(crate) mod array_vec;
 pub(crate) mod dyn_query;
 pub(crate) mod static_query;
 
+pub use bundle::Bundle;
+pub use command_buffer::CommandBuffer;
 pub use dyn_query::DynQuery;
 pub use dyn_query::FetchType;
 pub use entities::EcsId;
+pub use error::BorrowError;
+pub use error::CloneError;
+pub use error::EcsError;
+#[cfg(feature = "serde")]
+pub use error::SerdeError;
+#[cfg(feature = "hierarchy")]
+pub use hierarchy::Children;
+#[cfg(feature = "hierarchy")]
+pub use hierarchy::Parent;
+pub use merge::EntityMap;
+pub use merge::MapEntities;
+pub use resource::Res;
+pub use resource::ResMut;
+pub use schedule::Schedule;
+#[cfg(feature = "serde")]
+pub use serialize::ComponentRegistry;
+pub use static_query::Added;
+pub use static_query::Changed;
 pub use static_query::EcsIds;
+pub use static_query::Resource;
 pub use static_query::StaticQuery;
+pub use static_query::With;
+pub use static_query::Without;
 pub use world::World;
 
 #[cfg(test)]
 mod tests {
     mod bitset_iterator;
     mod bitsetsss;
+    mod clone;
+    mod command_buffer;
     mod dyn_query;
     mod entities;
+    #[cfg(feature = "hierarchy")]
+    mod hierarchy;
+    mod merge;
     mod query;
+    mod relation;
+    mod resource;
+    mod schedule;
+    #[cfg(feature = "serde")]
+    mod serialize;
     mod world;
 }
 
 pub(crate) mod utils {
+    use crate::world::ComponentMeta;
+    use std::alloc::{alloc, dealloc, handle_alloc_error};
     use std::hash::Hasher;
     use std::{convert::TryInto, sync::RwLockReadGuard};
     use std::{hash::BuildHasher, sync::RwLockWriteGuard};
@@ -75,6 +121,75 @@ pub(crate) mod utils {
         }
     }
 
+    /// Hasher for sets of component ids, unlike ``TypeIdHasher`` every written value is mixed into the hash.
+    /// Uses the same multiply-rotate scheme as rustc's FxHasher which is fast for small integer keys
+    #[derive(Default)]
+    pub struct ComponentSetHasher(u64);
+
+    impl ComponentSetHasher {
+        fn add(&mut self, word: u64) {
+            self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+        }
+    }
+
+    impl Hasher for ComponentSetHasher {
+        fn write(&mut self, bytes: &[u8]) {
+            for chunk in bytes.chunks(8) {
+                let mut word = [0; 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+                self.add(u64::from_ne_bytes(word));
+            }
+        }
+        fn write_u64(&mut self, n: u64) {
+            self.add(n);
+        }
+        fn write_usize(&mut self, n: usize) {
+            self.add(n as u64);
+        }
+        fn finish(&self) -> u64 {
+            self.0
+        }
+    }
+
+    #[derive(Clone, Default)]
+    pub struct ComponentSetHasherBuilder();
+
+    impl BuildHasher for ComponentSetHasherBuilder {
+        type Hasher = ComponentSetHasher;
+
+        fn build_hasher(&self) -> Self::Hasher {
+            ComponentSetHasher::default()
+        }
+    }
+
+    /// Drops a component that is stored at a potentially unaligned address by moving it into an aligned allocation first
+    ///
+    /// # Safety
+    ///
+    ///   ``ptr`` must point to a valid instance of the type described by ``meta`` which must not be used again
+    pub(crate) unsafe fn drop_unaligned(ptr: *mut u8, meta: &ComponentMeta) {
+        let drop_fn = match meta.drop_fn {
+            Some(drop_fn) => drop_fn,
+            None => return,
+        };
+
+        if meta.layout.size() == 0 {
+            // Zero sized types only need a non-null aligned pointer
+            drop_fn(meta.layout.align() as *mut _);
+            return;
+        }
+
+        unsafe {
+            let aligned = alloc(meta.layout);
+            if aligned.is_null() {
+                handle_alloc_error(meta.layout);
+            }
+            std::ptr::copy_nonoverlapping(ptr, aligned, meta.layout.size());
+            drop_fn(aligned as *mut _);
+            dealloc(aligned, meta.layout);
+        }
+    }
+
     pub(crate) fn index_twice_mut<T>(
         idx_1: usize,
         idx_2: usize,
@@ -0,0 +1,180 @@
+use crate::world::{ArchIndex, EntityMeta, InstanceMeta};
+use crate::{Component, EcsId, World};
+use std::collections::HashMap;
+
+/// Maps the ids of a merged ``World`` to the ids they were given in the world they were merged into, returned by
+/// ``World::merge_from``
+#[derive(Debug, Default)]
+pub struct EntityMap(HashMap<EcsId, EcsId>);
+
+impl EntityMap {
+    /// The id ``entity`` was given by the merge, None if it wasn't alive in the merged world
+    pub fn get(&self, entity: EcsId) -> Option<EcsId> {
+        self.0.get(&entity).copied()
+    }
+
+    /// ``(merged id, new id)`` pairs
+    pub fn iter(&self) -> impl Iterator<Item = (EcsId, EcsId)> + '_ {
+        self.0.iter().map(|(&from, &to)| (from, to))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The id of the component ``comp_id`` of ``other`` after merging, pairs are mapped by their relation and target
+    fn map_component(&self, other: &World, comp_id: EcsId) -> EcsId {
+        match comp_id.is_pair() {
+            true => {
+                let relation = other.entities.alive_at(comp_id.relation_index()).unwrap();
+                let target = other.entities.alive_at(comp_id.uindex()).unwrap();
+                EcsId::pair(self.0[&relation], self.0[&target])
+            }
+            false => self.0[&comp_id],
+        }
+    }
+}
+
+/// Implemented by components that hold ``EcsId``s so that they can point to the new ids after ``World::merge_from``,
+/// the component has to be registered with ``World::register_map_entities`` in either world. Ids that aren't in the
+/// ``EntityMap`` were already dead in the merged world
+pub trait MapEntities {
+    fn map_entities(&mut self, entity_map: &EntityMap);
+}
+
+fn map_entities_fn<T: Component + MapEntities>(ptr: *mut u8, entity_map: &EntityMap) {
+    unsafe { &mut *(ptr as *mut T) }.map_entities(entity_map)
+}
+
+impl World {
+    /// Makes ``World::merge_from`` call ``MapEntities::map_entities`` on every merged ``T``
+    pub fn register_map_entities<T: Component + MapEntities>(&mut self) {
+        let comp_id = self.get_or_create_type_id_ecsid::<T>();
+        let meta = self.ecs_id_meta[comp_id.uindex()].as_mut().unwrap();
+        meta.component_meta.map_entities_fn = Some(map_entities_fn::<T>);
+    }
+
+    /// Moves every entity of ``other`` into this world, each entity is given a fresh id and its components are
+    /// appended to the archetype with the same components in this world. Components of a type that both worlds
+    /// have are translated to this world's id for the type, the entities ``other`` created for those types are not
+    /// merged. Merged components are marked as added at the current change tick.
+    ///
+    /// The resource entity of ``other`` is not merged so the resources of ``other`` are dropped
+    pub fn merge_from(&mut self, mut other: World) -> EntityMap {
+        self.flush_reserved();
+        other.flush_reserved();
+        if let Some(resource_entity) = other.resource_entity.take() {
+            other.despawn(resource_entity);
+        }
+
+        let mut entity_map = EntityMap(HashMap::new());
+        let mut existing = Vec::new();
+        for (type_id, &other_id) in other.type_id_to_ecs_id.iter() {
+            if let Some(&comp_id) = self.type_id_to_ecs_id.get(type_id) {
+                entity_map.0.insert(other_id, comp_id);
+                existing.push(other_id);
+            }
+        }
+
+        // Every component meta has to be known before archetypes can be created, instance metas are filled in as
+        // entities are appended to their archetypes
+        for idx in 0..other.entities.generations.len() {
+            let other_entity = match other.entities.alive_at(idx) {
+                Some(other_entity) if !entity_map.0.contains_key(&other_entity) => other_entity,
+                _ => continue,
+            };
+            let entity = self.entities.spawn();
+            entity_map.0.insert(other_entity, entity);
+            let meta = EntityMeta {
+                instance_meta: InstanceMeta {
+                    archetype: ArchIndex(usize::MAX),
+                    index: usize::MAX,
+                },
+                component_meta: other.ecs_id_meta[idx]
+                    .as_ref()
+                    .unwrap()
+                    .component_meta
+                    .clone(),
+            };
+            self.set_entity_meta(entity, meta);
+        }
+        for (&type_id, other_id) in other.type_id_to_ecs_id.iter() {
+            self.type_id_to_ecs_id
+                .entry(type_id)
+                .or_insert(entity_map.0[other_id]);
+        }
+        for other_id in existing {
+            let other_meta = other.get_entity_meta(other_id).unwrap().clone();
+            let comp_id = entity_map.0[&other_id];
+            let meta = self.ecs_id_meta[comp_id.uindex()].as_mut().unwrap();
+            if let Some(map_entities_fn) = other_meta.component_meta.map_entities_fn {
+                meta.component_meta.map_entities_fn = Some(map_entities_fn);
+            }
+
+            let InstanceMeta { archetype, index } = other_meta.instance_meta;
+            other.archetypes[archetype.0].despawn(other_id, index, &mut other.ecs_id_meta);
+        }
+
+        let change_tick = self.change_tick();
+        for other_idx in 0..other.archetypes.len() {
+            if other.archetypes[other_idx].entities.is_empty() {
+                continue;
+            }
+
+            let comp_ids = other.archetypes[other_idx]
+                .comp_ids
+                .iter()
+                .map(|&id| entity_map.map_component(&other, id))
+                .collect::<Vec<_>>();
+            let map_entities_fns = comp_ids
+                .iter()
+                .map(|&comp_id| self.get_component_meta(comp_id).unwrap().map_entities_fn)
+                .collect::<Vec<_>>();
+
+            let mut sorted_comp_ids = comp_ids.clone();
+            sorted_comp_ids.sort();
+            let archetype_idx = match self.find_archetype_dynamic(&sorted_comp_ids) {
+                Some(archetype_idx) => archetype_idx,
+                None => self.create_archetype_dynamic(sorted_comp_ids),
+            };
+
+            let other_archetype = &mut other.archetypes[other_idx];
+            let archetype = &mut self.archetypes[archetype_idx.0];
+            let start = archetype.entities.len();
+            for (n, (_, other_column)) in other_archetype.component_storages.iter_mut().enumerate()
+            {
+                let column = archetype.component_storages[archetype.comp_lookup[&comp_ids[n]]]
+                    .1
+                    .get_mut();
+                // Safe because both columns were created from the same component meta
+                unsafe { other_column.get_mut().move_all_to_other_vec(column) };
+                column.added[start..].fill(change_tick);
+                column.changed[start..].fill(change_tick);
+
+                if let Some(map_entities_fn) = map_entities_fns[n] {
+                    for row in start..column.len() {
+                        map_entities_fn(column.data.get_raw(row).unwrap() as *mut u8, &entity_map);
+                    }
+                }
+            }
+
+            for (n, other_entity) in other_archetype.entities.drain(..).enumerate() {
+                let entity = entity_map.0[&other_entity];
+                archetype.entities.push(entity);
+                self.ecs_id_meta[entity.uindex()]
+                    .as_mut()
+                    .unwrap()
+                    .instance_meta = InstanceMeta {
+                    archetype: archetype_idx.clone(),
+                    index: start + n,
+                };
+            }
+        }
+
+        entity_map
+    }
+}
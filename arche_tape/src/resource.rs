@@ -0,0 +1,36 @@
+use crate::utils::EitherGuard;
+use std::ops::{Deref, DerefMut};
+
+/// A shared borrow of a resource returned by ``World::resource``
+pub struct Res<'a, T> {
+    pub(crate) _guard: EitherGuard<'a>,
+    pub(crate) resource: &'a T,
+}
+
+impl<'a, T> Deref for Res<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.resource
+    }
+}
+
+/// A mutable borrow of a resource returned by ``World::resource_mut``
+pub struct ResMut<'a, T> {
+    pub(crate) _guard: EitherGuard<'a>,
+    pub(crate) resource: &'a mut T,
+}
+
+impl<'a, T> Deref for ResMut<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.resource
+    }
+}
+
+impl<'a, T> DerefMut for ResMut<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.resource
+    }
+}
@@ -0,0 +1,309 @@
+use crate::{
+    static_query::{QueryParam, QueryTuple},
+    Added, Changed, Component, EcsId, EcsIds, FetchType, ResMut, Resource, StaticQuery, With,
+    Without, World,
+};
+use std::any::TypeId;
+
+/// Query params that can be fetched by a system running on a thread other than the one that owns the ``World``
+///
+/// # Safety
+/// Components that are borrowed immutably must be ``Sync`` and components that are borrowed mutably must be ``Send``
+pub unsafe trait SendQueryParam: QueryParam {}
+
+unsafe impl<T: Component + Sync> SendQueryParam for &'static T {}
+unsafe impl<T: Component + Send> SendQueryParam for &'static mut T {}
+unsafe impl<T: Component + Sync> SendQueryParam for Option<&'static T> {}
+unsafe impl<T: Component + Send> SendQueryParam for Option<&'static mut T> {}
+unsafe impl SendQueryParam for EcsIds {}
+unsafe impl<T: Component> SendQueryParam for With<T> {}
+unsafe impl<T: Component> SendQueryParam for Without<T> {}
+unsafe impl<T: Component> SendQueryParam for Added<T> {}
+unsafe impl<T: Component> SendQueryParam for Changed<T> {}
+unsafe impl<T: Component + Sync> SendQueryParam for Resource<T> {}
+
+/// A ``QueryTuple`` made of ``SendQueryParam``s, only these can be used as the query of a system in a ``Schedule``
+///
+/// # Safety
+/// Every param of the tuple must be a ``SendQueryParam``
+pub unsafe trait SendQueryTuple: QueryTuple {}
+
+macro_rules! impl_send_query_tuple {
+    ($($T:ident)*) => {
+        unsafe impl<$($T: SendQueryParam),*> SendQueryTuple for ($($T,)*) {}
+    };
+}
+
+impl_send_query_tuple!(A B C D E F G H J K);
+impl_send_query_tuple!(A B C D E F G H J);
+impl_send_query_tuple!(A B C D E F G H);
+impl_send_query_tuple!(A B C D E F G);
+impl_send_query_tuple!(A B C D E F);
+impl_send_query_tuple!(A B C D E);
+impl_send_query_tuple!(A B C D);
+impl_send_query_tuple!(A B C);
+impl_send_query_tuple!(A B);
+impl_send_query_tuple!(A);
+
+/// Identifies a system that was added to a ``Schedule``
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SystemId(usize);
+
+/// A type erased system, called with the world and the change tick the system last ran at
+type SystemFn = Box<dyn FnMut(&World, u32) + Send>;
+
+struct System {
+    name: &'static str,
+    fetch_types: fn(&World) -> Vec<FetchType>,
+    func: SystemFn,
+    /// The change tick of the stage this system last ran in, passed to ``StaticQuery::since``
+    last_run: u32,
+}
+
+impl System {
+    fn run(&mut self, world: &World, change_tick: u32) {
+        (self.func)(world, self.last_run);
+        self.last_run = change_tick;
+    }
+}
+
+/// The components a system reads and writes, derived from the ``FetchType``s of its query
+struct Access {
+    reads: Vec<EcsId>,
+    writes: Vec<EcsId>,
+}
+
+impl Access {
+    fn new(fetches: &[FetchType]) -> Self {
+        let mut access = Self {
+            reads: Vec::new(),
+            writes: Vec::new(),
+        };
+        for fetch in fetches {
+            match fetch {
+                &FetchType::Mut(id) | &FetchType::OptionalMut(id) => access.writes.push(id),
+                // Added and Changed read the ticks that Mut fetches write to
+                &FetchType::Immut(id)
+                | &FetchType::OptionalImmut(id)
+                | &FetchType::Added(id)
+                | &FetchType::Changed(id)
+                | &FetchType::Resource(id) => access.reads.push(id),
+                FetchType::EcsId | FetchType::With(_) | FetchType::Without(_) => (),
+            }
+        }
+        access
+    }
+
+    fn conflicts(&self, other: &Access) -> bool {
+        self.writes
+            .iter()
+            .any(|id| other.writes.contains(id) || other.reads.contains(id))
+            || other.writes.iter().any(|id| self.reads.contains(id))
+    }
+}
+
+/// Shares the world with the systems of a stage that run on rayon threads
+///
+/// Safe because systems can only access the world through the ``StaticQuery`` of their ``SendQueryTuple`` and the
+/// ``ResMut`` of a ``Send`` resource, so every component they borrow is ``Sync`` if read and ``Send`` if written, and systems in the same stage never conflict so
+/// no two threads access a component while one of them is writing to it
+#[cfg(feature = "rayon")]
+struct SyncWorld<'a>(&'a World);
+
+#[cfg(feature = "rayon")]
+unsafe impl Sync for SyncWorld<'_> {}
+
+/// Runs systems in stages, systems that don't read or write each other's components are placed in the same stage and
+/// run in parallel when the ``rayon`` feature is enabled.
+///
+/// Systems that conflict run in the order they were added unless ``Schedule::add_ordering`` says otherwise
+pub struct Schedule {
+    systems: Vec<System>,
+    /// ``(before, after)`` pairs of systems
+    orderings: Vec<(SystemId, SystemId)>,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self {
+            systems: Vec::new(),
+            orderings: Vec::new(),
+        }
+    }
+
+    /// Adds a system that is called with a query of ``Q`` every time the schedule runs. The query is made with
+    /// ``StaticQuery::since`` the last time the system ran, which only affects ``Added`` and ``Changed`` params
+    pub fn add_system<Q: SendQueryTuple, F: FnMut(StaticQuery<Q>) + Send + 'static>(
+        &mut self,
+        name: &'static str,
+        mut system: F,
+    ) -> SystemId {
+        self.systems.push(System {
+            name,
+            fetch_types: Q::fetch_types,
+            func: Box::new(move |world, last_run| system(Q::new(world).since(last_run))),
+            last_run: 0,
+        });
+        SystemId(self.systems.len() - 1)
+    }
+
+    /// Adds a system like ``Schedule::add_system`` that is also given mutable access to the resource ``R``. The
+    /// resource is a write in the system's access so systems that read or write ``R`` never share its stage.
+    ///
+    /// The system isn't called while ``R`` hasn't been inserted, it panics if ``Q`` also borrows ``R``
+    pub fn add_system_with_resource<
+        Q: SendQueryTuple,
+        R: Component + Send,
+        F: FnMut(StaticQuery<Q>, ResMut<R>) + Send + 'static,
+    >(
+        &mut self,
+        name: &'static str,
+        mut system: F,
+    ) -> SystemId {
+        self.systems.push(System {
+            name,
+            fetch_types: |world| {
+                let mut fetches = Q::fetch_types(world);
+                if let Some(&id) = world.type_id_to_ecs_id.get(&TypeId::of::<R>()) {
+                    fetches.push(FetchType::Mut(id));
+                }
+                fetches
+            },
+            func: Box::new(move |world, last_run| {
+                if let Some(resource) = world.resource_mut::<R>() {
+                    system(Q::new(world).since(last_run), resource);
+                }
+            }),
+            last_run: 0,
+        });
+        SystemId(self.systems.len() - 1)
+    }
+
+    /// Makes ``before`` run in an earlier stage than ``after``
+    pub fn add_ordering(&mut self, before: SystemId, after: SystemId) {
+        assert!(before.0 < self.systems.len() && after.0 < self.systems.len());
+        self.orderings.push((before, after));
+    }
+
+    /// Returns the names of the systems in each stage, in the order the stages run
+    ///
+    /// Panics if the ordering constraints contain a cycle
+    pub fn stages(&self, world: &World) -> Vec<Vec<&'static str>> {
+        self.build_stages(world)
+            .into_iter()
+            .map(|stage| {
+                stage
+                    .into_iter()
+                    .map(|system| self.systems[system].name)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Describes the stages the schedule would run on ``world`` without running any systems, one line per stage
+    ///
+    /// Panics if the ordering constraints contain a cycle
+    pub fn dry_run(&self, world: &World) -> String {
+        self.stages(world)
+            .iter()
+            .enumerate()
+            .map(|(n, stage)| format!("stage {}: {}\n", n, stage.join(", ")))
+            .collect()
+    }
+
+    /// Runs every stage in order, each stage has its own change tick
+    ///
+    /// Panics if the ordering constraints contain a cycle
+    pub fn run(&mut self, world: &mut World) {
+        let world = &*world;
+        for stage in self.build_stages(world) {
+            let change_tick = world.change_tick();
+            let systems = self
+                .systems
+                .iter_mut()
+                .enumerate()
+                .filter(|(n, _)| stage.contains(n))
+                .map(|(_, system)| system);
+
+            #[cfg(feature = "rayon")]
+            if stage.len() > 1 {
+                let world = &SyncWorld(world);
+                rayon::scope(|scope| {
+                    for system in systems {
+                        scope.spawn(move |_| system.run(world.0, change_tick));
+                    }
+                });
+                world.0.increment_change_tick();
+                continue;
+            }
+
+            for system in systems {
+                system.run(world, change_tick);
+            }
+            world.increment_change_tick();
+        }
+    }
+
+    /// Systems sorted so that every system comes after the systems ordered before it, ties are broken by the order
+    /// the systems were added in
+    fn sorted_systems(&self) -> Vec<usize> {
+        let mut unsorted_befores = vec![0; self.systems.len()];
+        for (_, after) in &self.orderings {
+            unsorted_befores[after.0] += 1;
+        }
+
+        let mut sorted = vec![false; self.systems.len()];
+        let mut order = Vec::with_capacity(self.systems.len());
+        while order.len() < self.systems.len() {
+            let next = (0..self.systems.len())
+                .find(|&n| !sorted[n] && unsorted_befores[n] == 0)
+                .expect("Ordering constraints of the schedule contain a cycle");
+            sorted[next] = true;
+            order.push(next);
+            for (before, after) in &self.orderings {
+                if before.0 == next {
+                    unsorted_befores[after.0] -= 1;
+                }
+            }
+        }
+        order
+    }
+
+    /// Places each system in the stage after the last system it is ordered after or conflicts with
+    fn build_stages(&self, world: &World) -> Vec<Vec<usize>> {
+        let accesses: Vec<_> = self
+            .systems
+            .iter()
+            .map(|system| Access::new(&(system.fetch_types)(world)))
+            .collect();
+
+        let order = self.sorted_systems();
+        let mut stage_of = vec![0; self.systems.len()];
+        let mut stages: Vec<Vec<usize>> = Vec::new();
+        for (n, &system) in order.iter().enumerate() {
+            let after_orderings = self
+                .orderings
+                .iter()
+                .filter(|(_, after)| after.0 == system)
+                .map(|(before, _)| stage_of[before.0] + 1);
+            let after_conflicts = order[..n]
+                .iter()
+                .filter(|&&earlier| accesses[earlier].conflicts(&accesses[system]))
+                .map(|&earlier| stage_of[earlier] + 1);
+
+            let stage = after_orderings.chain(after_conflicts).max().unwrap_or(0);
+            stage_of[system] = stage;
+            if stage == stages.len() {
+                stages.push(Vec::new());
+            }
+            stages[stage].push(system);
+        }
+        stages
+    }
+}
@@ -0,0 +1,376 @@
+use crate::entities::MAX_INDEX;
+use crate::world::{ArchIndex, Column, ComponentMeta, ComponentTicks, EntityMeta, InstanceMeta};
+use crate::{Component, EcsId, SerdeError, World};
+use bincode::de::read::SliceReader;
+use bincode::{DefaultOptions, ErrorKind};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::atomic::AtomicU32;
+
+type Serializer<'a> = bincode::Serializer<&'a mut dyn Write, DefaultOptions>;
+type Deserializer<'a> = bincode::Deserializer<SliceReader<'a>, DefaultOptions>;
+
+type SerializeFn = unsafe fn(*const u8, &mut Serializer<'_>) -> Result<(), bincode::Error>;
+type DeserializeFn =
+    unsafe fn(&mut Deserializer<'_>, &mut Column, ComponentTicks) -> Result<(), bincode::Error>;
+
+/// A component that was registered with a ``ComponentRegistry``
+#[derive(Clone)]
+pub struct RegisteredComponent {
+    pub meta: ComponentMeta,
+    type_id: TypeId,
+    serialize_fn: SerializeFn,
+    deserialize_fn: DeserializeFn,
+}
+
+/// # Safety
+///
+///    ``ptr`` must point to a valid ``T``
+unsafe fn serialize_fn<T: Serialize>(
+    ptr: *const u8,
+    serializer: &mut Serializer<'_>,
+) -> Result<(), bincode::Error> {
+    unsafe { &*(ptr as *const T) }.serialize(serializer)
+}
+
+/// # Safety
+///
+///    ``column`` must store ``T``s
+unsafe fn deserialize_fn<T: DeserializeOwned>(
+    deserializer: &mut Deserializer<'_>,
+    column: &mut Column,
+    ticks: ComponentTicks,
+) -> Result<(), bincode::Error> {
+    let mut component = core::mem::ManuallyDrop::new(T::deserialize(deserializer)?);
+    unsafe {
+        column.push_raw(
+            &mut *component as *mut T as *mut core::mem::MaybeUninit<u8>,
+            ticks,
+        )
+    };
+    Ok(())
+}
+
+/// Maps stable names to the components that ``World::save`` and ``World::load`` can serialize, names are stored in
+/// the saved world in place of ``TypeId``s which aren't stable across builds
+#[derive(Clone, Default)]
+pub struct ComponentRegistry {
+    components: HashMap<&'static str, RegisteredComponent>,
+    names: HashMap<TypeId, &'static str>,
+}
+
+impl ComponentRegistry {
+    pub fn new() -> Self {
+        Self {
+            components: HashMap::new(),
+            names: HashMap::new(),
+        }
+    }
+
+    /// Registers ``T`` under ``name``, panics if another type is already registered under the same name
+    pub fn register<T: Component + Serialize + DeserializeOwned>(&mut self, name: &'static str) {
+        let type_id = TypeId::of::<T>();
+        if let Some(registered) = self.components.get(name) {
+            assert!(
+                registered.type_id == type_id,
+                "Attempted to register two components as {}",
+                name
+            );
+        }
+
+        self.components.insert(
+            name,
+            RegisteredComponent {
+                meta: ComponentMeta::from_generic::<T>(),
+                type_id,
+                serialize_fn: serialize_fn::<T>,
+                deserialize_fn: deserialize_fn::<T>,
+            },
+        );
+        self.names.insert(type_id, name);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&RegisteredComponent> {
+        self.components.get(name)
+    }
+
+    /// The name ``T`` was registered under
+    pub fn name<T: Component>(&self) -> Option<&'static str> {
+        self.names.get(&TypeId::of::<T>()).copied()
+    }
+}
+
+/// The start of a saved world, followed by ``archetypes`` archetypes that are each written as their entities and
+/// component ids followed by the ticks and then the data of each column in turn
+#[derive(Serialize, Deserialize)]
+struct SavedWorld {
+    change_tick: u32,
+    /// ``Entities::generations``
+    generations: Vec<(bool, u32)>,
+    /// ``Entities::despawned``, the indices that will be reused by the next spawns
+    free_indices: Vec<usize>,
+    /// ``World::despawned``
+    despawned: Vec<EcsId>,
+    resource_entity: Option<EcsId>,
+    /// The registered names of the components created for a type, every other entity has a unit ``ComponentMeta``
+    names: Vec<(EcsId, String)>,
+    archetypes: usize,
+}
+
+fn malformed(msg: &str) -> SerdeError {
+    SerdeError::Format(Box::new(ErrorKind::Custom(msg.to_string())))
+}
+
+impl World {
+    /// Components have to be registered before a world holding them can be saved or loaded
+    pub fn component_registry_mut(&mut self) -> &mut ComponentRegistry {
+        &mut self.component_registry
+    }
+
+    /// Writes every entity and component to ``writer`` with ``bincode``, see ``World::load``. Ids are saved
+    /// as is so components holding ``EcsId``s stay valid after loading. Every component that holds data must be
+    /// registered, entities used as dataless components and pairs of a registered relation are saved without needing
+    /// to be registered
+    ///
+    /// Components are written one at a time straight from their columns so ``writer`` should be buffered
+    pub fn save<W: Write>(&mut self, mut writer: W) -> Result<(), SerdeError> {
+        self.flush_reserved();
+        let serializer = &mut Serializer::new(&mut writer as &mut dyn Write, DefaultOptions::new());
+
+        let mut names = Vec::new();
+        let mut registered = HashMap::new();
+        for (type_id, &id) in self.type_id_to_ecs_id.iter() {
+            if let Some(&name) = self.component_registry.names.get(type_id) {
+                names.push((id, name.to_string()));
+                registered.insert(id, &self.component_registry.components[name]);
+            }
+        }
+        // Sorted so that saving the same world always writes the same bytes
+        names.sort_unstable_by_key(|&(id, _)| id);
+
+        let archetypes = self.archetypes.iter().filter(|a| !a.entities.is_empty());
+        SavedWorld {
+            change_tick: self.change_tick(),
+            generations: self.entities.generations.clone(),
+            free_indices: self.entities.despawned.clone(),
+            despawned: self.despawned.clone(),
+            resource_entity: self.resource_entity,
+            names,
+            archetypes: archetypes.clone().count(),
+        }
+        .serialize(&mut *serializer)?;
+
+        for archetype in archetypes {
+            (&archetype.entities, &archetype.comp_ids).serialize(&mut *serializer)?;
+            for (id, column) in archetype.component_storages.iter() {
+                // Safe because saving takes &mut self so no queries can be accessing the column
+                let column = unsafe { &*column.get() };
+                (&column.added, &column.changed).serialize(&mut *serializer)?;
+                if self.get_component_meta(*id).unwrap().is_unit {
+                    continue;
+                }
+
+                let component = match id.is_pair() {
+                    true => self.entities.alive_at(id.relation_index()).unwrap(),
+                    false => *id,
+                };
+                let registered = registered
+                    .get(&component)
+                    .ok_or(SerdeError::Unregistered(*id))?;
+                for n in 0..column.len() {
+                    let ptr = column.data.get_raw(n).unwrap();
+                    // Safe because the serialize fn was registered for the component stored in the column
+                    unsafe { (registered.serialize_fn)(ptr, serializer) }?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the contents of the world with a world written by ``World::save``, the component registry is kept.
+    /// The world is left untouched if an error is returned
+    ///
+    /// ``reader`` is read to the end before anything is deserialized so that lengths in a malformed save can't
+    /// cause allocations larger than the save itself
+    ///
+    /// Entities that were created with a ``ComponentMeta`` that isn't registered are loaded with a unit
+    /// ``ComponentMeta`` as their meta is not saved. Registered components in the saved world keep the hooks that
+    /// this world registered for their type with ``World::register_clone``, ``World::register_debug`` and
+    /// ``World::register_map_entities``
+    pub fn load<R: Read>(&mut self, mut reader: R) -> Result<(), SerdeError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|err| SerdeError::Format(Box::new(ErrorKind::Io(err))))?;
+        let deserializer = &mut Deserializer::from_slice(&bytes, DefaultOptions::new());
+        let saved = SavedWorld::deserialize(&mut *deserializer)?;
+
+        // Every id has to be checked against the generations before it is looked up in ``Entities``
+        let len = saved.generations.len();
+        let valid_entity = |id: &EcsId| !id.is_pair() && id.uindex() < len;
+        let valid_component = |id: &EcsId| match id.is_pair() {
+            true => id.relation_index() < len && id.uindex() < len,
+            false => id.uindex() < len,
+        };
+        let valid_ids = saved.despawned.iter().all(valid_entity)
+            && saved.resource_entity.iter().all(valid_entity)
+            && saved.names.iter().map(|(id, _)| id).all(valid_entity);
+        if !valid_ids {
+            return Err(malformed("Entity index is out of range"));
+        }
+
+        // Spawning relies on every free index being a distinct dead slot and on entities never looking like pairs
+        let mut free = vec![false; saved.generations.len()];
+        for &idx in saved.free_indices.iter() {
+            match saved.generations.get(idx) {
+                Some(&(false, _)) if !free[idx] => free[idx] = true,
+                _ => return Err(malformed("Free index is not a dead entity")),
+            }
+        }
+        let valid_generation = |&(_, gen): &(bool, u32)| !EcsId::new(0, gen).is_pair();
+        if saved.generations.len() > MAX_INDEX + 1
+            || !saved.generations.iter().all(valid_generation)
+        {
+            return Err(malformed("Entity index or generation is out of range"));
+        }
+
+        let mut world = World::new();
+        world.component_registry = self.component_registry.clone();
+        world.entities.generations = saved.generations;
+        world.entities.despawned = saved.free_indices;
+        world.change_tick = AtomicU32::new(saved.change_tick);
+        world.despawned = saved.despawned;
+        world.resource_entity = saved.resource_entity;
+
+        // Every component meta has to be known before archetypes can be created, instance metas are filled in as
+        // entities are placed in their archetypes
+        const UNPLACED: usize = usize::MAX;
+        for idx in 0..world.entities.generations.len() {
+            if let Some(entity) = world.entities.alive_at(idx) {
+                let meta = EntityMeta {
+                    instance_meta: InstanceMeta {
+                        archetype: ArchIndex(UNPLACED),
+                        index: UNPLACED,
+                    },
+                    component_meta: ComponentMeta::unit(),
+                };
+                world.set_entity_meta(entity, meta);
+            }
+        }
+        for (id, name) in saved.names {
+            let registered = world
+                .component_registry
+                .get(&name)
+                .ok_or(SerdeError::UnknownName(name))?
+                .clone();
+            if !world.entities.is_alive(id) {
+                return Err(malformed("Registered component is not alive"));
+            }
+            let mut meta = registered.meta;
+            let old_id = self.type_id_to_ecs_id.get(&registered.type_id);
+            if let Some(old_meta) = old_id.and_then(|&id| self.get_component_meta(id)) {
+                meta.clone_fn = old_meta.clone_fn;
+                meta.debug_fn = old_meta.debug_fn;
+                meta.map_entities_fn = old_meta.map_entities_fn;
+            }
+            world.ecs_id_meta[id.uindex()]
+                .as_mut()
+                .unwrap()
+                .component_meta = meta;
+            world.type_id_to_ecs_id.insert(registered.type_id, id);
+        }
+
+        let mut registered = HashMap::new();
+        for (&type_id, &id) in world.type_id_to_ecs_id.iter() {
+            let name = world.component_registry.names[&type_id];
+            registered.insert(id, world.component_registry.components[name].deserialize_fn);
+        }
+
+        for _ in 0..saved.archetypes {
+            let (entities, comp_ids) = <(Vec<EcsId>, Vec<EcsId>)>::deserialize(&mut *deserializer)?;
+            if !entities.iter().all(valid_entity) || !comp_ids.iter().all(valid_component) {
+                return Err(malformed("Entity index is out of range"));
+            }
+            let sorted = comp_ids.windows(2).all(|ids| ids[0] < ids[1]);
+            if !sorted || !comp_ids.iter().all(|&id| world.is_alive_component(id)) {
+                return Err(malformed("Archetype has invalid components"));
+            }
+            if world.find_archetype_dynamic(&comp_ids).is_some() {
+                return Err(malformed("Archetype is saved twice"));
+            }
+            let archetype_idx = world.create_archetype_dynamic(comp_ids.clone());
+
+            for (n, id) in comp_ids.into_iter().enumerate() {
+                let (added, changed) = <(Vec<u32>, Vec<u32>)>::deserialize(&mut *deserializer)?;
+                if added.len() != entities.len() || changed.len() != entities.len() {
+                    return Err(malformed(
+                        "Column length doesn't match the archetype's entities",
+                    ));
+                }
+                let ticks = added
+                    .into_iter()
+                    .zip(changed)
+                    .map(|(added, changed)| ComponentTicks { added, changed });
+
+                let component = match id.is_pair() {
+                    true => world.entities.alive_at(id.relation_index()).unwrap(),
+                    false => id,
+                };
+                let is_unit = world.get_component_meta(id).unwrap().is_unit;
+                let column = world.archetypes[archetype_idx.0].component_storages[n]
+                    .1
+                    .get_mut();
+                if is_unit {
+                    for ticks in ticks {
+                        let dangling = core::ptr::NonNull::dangling().as_ptr();
+                        unsafe { column.push_raw(dangling, ticks) };
+                    }
+                    continue;
+                }
+
+                let deserialize_fn = *registered
+                    .get(&component)
+                    .ok_or(SerdeError::Unregistered(id))?;
+                for ticks in ticks {
+                    // Safe because the column was created from the registered meta of the component
+                    unsafe { deserialize_fn(deserializer, column, ticks) }?;
+                }
+            }
+
+            for (index, &entity) in entities.iter().enumerate() {
+                let meta = match world.get_entity_meta(entity) {
+                    Some(meta) if meta.instance_meta.index == UNPLACED => meta,
+                    _ => return Err(malformed("Archetype has a dead or duplicate entity")),
+                };
+                let mut meta = meta.clone();
+                meta.instance_meta = InstanceMeta {
+                    archetype: archetype_idx.clone(),
+                    index,
+                };
+                world.set_entity_meta(entity, meta);
+            }
+            world.archetypes[archetype_idx.0].entities = entities;
+        }
+
+        let all_placed = world
+            .ecs_id_meta
+            .iter()
+            .flatten()
+            .all(|meta| meta.instance_meta.index != UNPLACED);
+        if !all_placed {
+            return Err(malformed("Entity is not in any archetype"));
+        }
+        if !world
+            .resource_entity
+            .iter()
+            .all(|&entity| world.is_alive(entity))
+        {
+            return Err(malformed("Resource entity is not alive"));
+        }
+
+        *self = world;
+        Ok(())
+    }
+}
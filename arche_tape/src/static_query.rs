@@ -1,5 +1,8 @@
-use crate::{utils::EitherGuard, world::Archetype, Component, EcsId, FetchType, World};
-use std::{any::TypeId, marker::PhantomData};
+use crate::{utils::EitherGuard, world::Archetype, BorrowError, Component, EcsId, FetchType, World};
+use std::{any::TypeId, marker::PhantomData, ptr::NonNull};
+
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 // If we remove the 'static bound here we are required to manually annotate 'static lifetimes for StaticQuery's in
 // arguments of functions even though QueryTuple has a 'static bound in its trait definition
@@ -7,28 +10,92 @@ pub struct StaticQuery<'a, Q: QueryTuple + 'static> {
     world: &'a World,
     _guards: <Q as QueryTuple>::Guard<'a>,
     fetches: Option<Q::Fetches>,
+    last_run: u32,
+    change_tick: u32,
     _p: PhantomData<Q>,
 }
 
+impl<'a, Q: QueryTuple + 'static> StaticQuery<'a, Q> {
+    /// Sets the tick that ``Added`` and ``Changed`` filters compare against, only components added/changed after
+    /// this tick will match. Defaults to 0 which matches every component
+    pub fn since(mut self, last_run: u32) -> Self {
+        self.last_run = last_run;
+        self
+    }
+}
+
 pub struct StaticQueryIter<'a, Q: QueryTuple + 'static> {
+    world: &'a World,
     fetches: Option<&'a Q::Fetches>,
     archetypes: <Q as QueryTuple>::ArchetypeIter<'a>,
     intra_iter: IntraArchetypeIter<'a, Q>,
+    last_run: u32,
+    change_tick: u32,
 }
 
 struct IntraArchetypeIter<'a, Q: QueryTuple> {
     remaining: usize,
     ptrs: Q::Ptrs,
+    /// Ticks of the components fetched by ``Added`` and ``Changed``, null for every other fetch
+    ticks: Q::Ticks,
+    /// Ticks of the components that are fetched mutably, null for every other fetch and for optional components
+    /// that the archetype doesn't have
+    changed: Q::Ticks,
+    fetches: Option<&'a Q::Fetches>,
+    last_run: u32,
+    change_tick: u32,
     _p: PhantomData<(Q, &'a Archetype)>,
 }
 
+/// Splits the ticks of an archetype's columns into the ``ticks`` and ``changed`` of an ``IntraArchetypeIter``
+fn split_ticks<const N: usize>(
+    fetches: &[FetchType; N],
+    archetype: &Archetype,
+) -> ([*mut u32; N], [*mut u32; N]) {
+    let mut ticks = [core::ptr::null_mut(); N];
+    let mut changed = [core::ptr::null_mut(); N];
+    for (n, fetch) in fetches.iter().enumerate() {
+        match fetch {
+            FetchType::Added(_) | FetchType::Changed(_) => {
+                ticks[n] = fetch.create_ticks_ptr(archetype)
+            }
+            FetchType::Mut(_) | FetchType::OptionalMut(_) => {
+                changed[n] = fetch.create_ticks_ptr(archetype)
+            }
+            _ => (),
+        }
+    }
+    (ticks, changed)
+}
+
+/// Rows per chunk that ``StaticQuery::par_iter`` splits archetypes into
+#[cfg(feature = "rayon")]
+const PAR_CHUNK_SIZE: usize = 1024;
+
+/// A chunk of rows of one archetype that is iterated on a rayon thread
+///
+/// Safe to send because every chunk covers a distinct range of rows so no two threads ever access the same component
+/// or ticks, the query's guards keep the columns locked while the chunks are alive and ``StaticQuery::par_iter``
+/// requires the items built from the chunk to be ``Send``
+#[cfg(feature = "rayon")]
+struct SendChunk<'a, Q: QueryTuple>(IntraArchetypeIter<'a, Q>);
+
+#[cfg(feature = "rayon")]
+unsafe impl<'a, Q: QueryTuple> Send for SendChunk<'a, Q> {}
+
 pub trait QueryTuple: Sized + 'static {
     type Guard<'a>;
     type ArchetypeIter<'a>;
     type Ptrs: Copy;
+    type Ticks: Copy;
     type Fetches;
 
     fn new(world: &World) -> StaticQuery<Self>;
+
+    fn try_new(world: &World) -> Result<StaticQuery<'_, Self>, BorrowError>;
+
+    /// The fetches this query would make on ``world``, empty if the query can't match any entities
+    fn fetch_types(world: &World) -> Vec<FetchType>;
 }
 
 macro_rules! impl_query_tuple {
@@ -37,16 +104,29 @@ macro_rules! impl_query_tuple {
             type Guard<'a> = [EitherGuard<'a>; $N];
             type ArchetypeIter<'a> = crate::world::ArchetypeIter<'a, $N>;
             type Ptrs = [*mut u8; $N];
+            type Ticks = [*mut u32; $N];
             type Fetches = [crate::FetchType; $N];
 
             fn new(world: &World) -> StaticQuery<Self> {
                 StaticQuery::<($($T,)*)>::new(world)
             }
+
+            fn try_new(world: &World) -> Result<StaticQuery<'_, Self>, BorrowError> {
+                StaticQuery::<($($T,)*)>::try_new(world)
+            }
+
+            fn fetch_types(world: &World) -> Vec<FetchType> {
+                (|| Some(vec![$($T::fetch_type(world)?,)*]))().unwrap_or_default()
+            }
         }
 
         impl<'a, $($T: QueryParam,)*> StaticQuery<'a, ($($T,)*)> {
-            #[allow(non_snake_case)]
             pub(crate) fn new(world: &'a World) -> Self {
+                Self::try_new(world).unwrap_or_else(|err| panic!("{}", err))
+            }
+
+            #[allow(non_snake_case)]
+            pub(crate) fn try_new(world: &'a World) -> Result<Self, BorrowError> {
                 let fetches = (|| {
                     Some([$(
                         $T::fetch_type(world)?,
@@ -54,12 +134,23 @@ macro_rules! impl_query_tuple {
                 })();
 
                 let guards = match &fetches {
-                    Some([$($T,)*]) => {
+                    Some(all_fetches @ [$($T,)*]) => {
+                        crate::dyn_query::check_aliasing(all_fetches)?;
                         [$(
                             match $T {
-                                FetchType::Mut(id) => EitherGuard::Write(world.locks[world.lock_lookup[id]].write().unwrap()),
-                                FetchType::Immut(id) => EitherGuard::Read(world.locks[world.lock_lookup[id]].read().unwrap()),
-                                FetchType::EcsId => EitherGuard::None,
+                                FetchType::Mut(id) | FetchType::OptionalMut(id) => world.try_lock(*id, true)?,
+                                FetchType::Immut(id)
+                                | FetchType::OptionalImmut(id)
+                                | FetchType::Resource(id) => world.try_lock(*id, false)?,
+                                // The ticks are already locked if another fetch borrows the same component
+                                FetchType::Added(id) | FetchType::Changed(id) => {
+                                    if all_fetches.iter().any(|fetch| fetch.locks_component(*id)) {
+                                        EitherGuard::None
+                                    } else {
+                                        world.try_lock(*id, false)?
+                                    }
+                                }
+                                FetchType::EcsId | FetchType::With(_) | FetchType::Without(_) => EitherGuard::None,
                             },
                         )*]
                     }
@@ -69,13 +160,15 @@ macro_rules! impl_query_tuple {
                     }
                 };
 
-                Self {
+                Ok(Self {
                     fetches,
                     world,
+                    last_run: 0,
+                    change_tick: world.change_tick(),
 
                     _guards: guards,
                     _p: PhantomData,
-                }
+                })
             }
 
             #[allow(non_snake_case)]
@@ -88,29 +181,58 @@ macro_rules! impl_query_tuple {
 
                 assert!(meta.index < archetype.entities.len());
 
-                let [$($T,)*] = self.fetches.as_ref()?;
-                let [$(mut $T,)*] = [$($T::create_ptr(archetype, $T)?,)*];
-                $(
-                    $T::offset_ptr(&mut $T, meta.index);
-                )*
+                let fetches = self.fetches.as_ref()?;
+                let [$($T,)*] = fetches;
+                let ptrs = [$($T::create_ptr(self.world, archetype, $T)?,)*];
+                let mut intra_iter = IntraArchetypeIter::<($($T,)*)>::new(
+                    archetype,
+                    meta.index,
+                    1,
+                    ptrs,
+                    fetches,
+                    self.last_run,
+                    self.change_tick,
+                );
+                let [$($T,)*] = intra_iter.next()?;
                 Some(($($T::cast_ptr($T),)*))
             }
 
             #[allow(unused_variables, non_snake_case)]
             pub fn iter(&mut self) -> StaticQueryIter<($($T,)*)> {
                 let identity: fn(_) -> _ = |x| x;
+                let negate: fn(_) -> _ = |x: usize| !x;
                 let archetype_iter: crate::world::ArchetypeIter<$N> = match &self.fetches {
                     Some([$($T,)*]) => {
                         let mut bitlength = self.world.entities_bitvec.len as u32;
                         let iters = [$(
                             match $T {
-                                FetchType::EcsId => {
+                                FetchType::EcsId
+                                | FetchType::OptionalMut(_)
+                                | FetchType::OptionalImmut(_)
+                                | FetchType::Resource(_) => {
                                     (self.world.entities_bitvec.data.iter(), identity)
                                 }
-                                FetchType::Immut(id) | FetchType::Mut(id) => {
-                                    let bitvec = self.world.archetype_bitset.get_bitvec(*id).unwrap();
-                                    bitlength = u32::min(bitlength, bitvec.len as u32);
-                                    (bitvec.data.iter(), identity)
+                                FetchType::Immut(id)
+                                | FetchType::Mut(id)
+                                | FetchType::With(id)
+                                | FetchType::Added(id)
+                                | FetchType::Changed(id) => {
+                                    match self.world.archetype_bitset.get_bitvec(*id) {
+                                        Some(bitvec) => {
+                                            bitlength = u32::min(bitlength, bitvec.len as u32);
+                                            (bitvec.data.iter(), identity)
+                                        }
+                                        None => {
+                                            bitlength = 0;
+                                            (self.world.entities_bitvec.data.iter(), identity)
+                                        }
+                                    }
+                                }
+                                FetchType::Without(id) => {
+                                    match self.world.archetype_bitset.get_bitvec(*id) {
+                                        Some(bitvec) => (bitvec.data.iter(), negate),
+                                        None => (self.world.entities_bitvec.data.iter(), identity),
+                                    }
                                 }
                             },
                         )*];
@@ -129,10 +251,99 @@ macro_rules! impl_query_tuple {
                 };
 
                 StaticQueryIter {
+                    world: self.world,
                     fetches: self.fetches.as_ref(),
                     archetypes: archetype_iter,
                     intra_iter: IntraArchetypeIter::<($($T,)*)>::unit(),
+                    last_run: self.last_run,
+                    change_tick: self.change_tick,
+                }
+            }
+        }
+
+        #[cfg(feature = "rayon")]
+        impl<'a, $($T: QueryParam,)*> StaticQuery<'a, ($($T,)*)> {
+            /// Same as ``StaticQuery::iter`` except the rows are iterated in parallel, large archetypes are split into
+            /// chunks of ``PAR_CHUNK_SIZE`` rows. Only available with the ``rayon`` feature
+            ///
+            /// The components stay locked by this query's guards for as long as the iterator is alive, the item type
+            /// being ``Send`` ensures that ``&T`` is only shared between threads if ``T: Sync`` and that ``&mut T`` is only
+            /// handed to another thread if ``T: Send``
+            #[allow(non_snake_case, unused_assignments)]
+            pub fn par_iter<'q>(&'q mut self) -> impl ParallelIterator<Item = ($(<$T as QueryParam>::Returns<'q>,)*)> + 'q
+            where
+                ($(<$T as QueryParam>::Returns<'q>,)*): Send,
+            {
+                let StaticQueryIter { world, fetches, archetypes, last_run, change_tick, .. } = self.iter();
+
+                let mut chunks = Vec::new();
+                if let Some(fetches) = fetches {
+                    for archetype in archetypes {
+                        let mut ptrs = [core::ptr::null_mut::<u8>(); $N];
+                        let mut n = 0;
+                        $({
+                            let fetch = &fetches[n];
+                            ptrs[n] = $T::create_ptr(world, archetype, fetch).unwrap();
+                            n += 1;
+                        })*
+
+                        let len = archetype.entities.len();
+                        for start in (0..len).step_by(PAR_CHUNK_SIZE) {
+                            let remaining = usize::min(PAR_CHUNK_SIZE, len - start);
+                            let chunk = IntraArchetypeIter::<($($T,)*)>::new(
+                                archetype,
+                                start,
+                                remaining,
+                                ptrs,
+                                fetches,
+                                last_run,
+                                change_tick,
+                            );
+                            chunks.push(SendChunk(chunk));
+                        }
+                    }
                 }
+
+                chunks.into_par_iter().flat_map_iter(|SendChunk(chunk)| {
+                    chunk.map(|[$($T,)*]| ($($T::cast_ptr($T),)*))
+                })
+            }
+
+            /// Calls ``f`` on every row of the query in parallel, see ``StaticQuery::par_iter``
+            pub fn par_for_each<'q, Func>(&'q mut self, f: Func)
+            where
+                Func: Fn(($(<$T as QueryParam>::Returns<'q>,)*)) + Send + Sync,
+                ($(<$T as QueryParam>::Returns<'q>,)*): Send,
+            {
+                self.par_iter().for_each(f);
+            }
+        }
+
+        impl<'a, $($T: QueryParam,)*> StaticQueryIter<'a, ($($T,)*)> {
+            /// Creates the iterator over the rows of the next matching archetype
+            #[allow(non_snake_case, unused_assignments)]
+            fn next_archetype(&mut self) -> Option<IntraArchetypeIter<'a, ($($T,)*)>> {
+                let archetype = self.archetypes.next()?;
+                let mut ptrs = [core::ptr::null_mut::<u8>(); $N];
+
+                let fetches = self.fetches.unwrap();
+                let mut n = 0;
+                $({
+                    let fetch = &fetches[n];
+                    let ptr = $T::create_ptr(self.world, archetype, fetch).unwrap();
+                    ptrs[n] = ptr;
+                    n += 1;
+                })*
+
+                Some(IntraArchetypeIter::<($($T,)*)>::new(
+                    archetype,
+                    0,
+                    archetype.entities.len(),
+                    ptrs,
+                    fetches,
+                    self.last_run,
+                    self.change_tick,
+                ))
             }
         }
 
@@ -149,58 +360,158 @@ macro_rules! impl_query_tuple {
                                     $($T::cast_ptr($T),)*
                                 ));
                             }
-                            None => {
-                                let archetype = self.archetypes.next()?;
-                                let mut ptrs = [0x0 as *mut u8; $N];
-
-                                let fetches = self.fetches.as_ref().unwrap();
-                                let mut n = 0;
-                                $({
-                                    let fetch = &fetches[n];
-                                    let ptr = $T::create_ptr(archetype, fetch).unwrap();
-                                    ptrs[n] = ptr;
-                                    n += 1;
-                                })*
-
-                                self.intra_iter = IntraArchetypeIter {
-                                    remaining: archetype.entities.len(),
-                                    ptrs,
-                                    _p: PhantomData,
-                                };
-                            },
+                            None => self.intra_iter = self.next_archetype()?,
                         }
                     }
                 }
+
+                /// Folds one archetype at a time so ``for_each`` gets a loop per archetype
+                #[allow(non_snake_case)]
+                fn fold<Acc, Func>(mut self, init: Acc, mut f: Func) -> Acc
+                where
+                    Func: FnMut(Acc, Self::Item) -> Acc,
+                {
+                    let mut fold_archetype = |acc, intra_iter: IntraArchetypeIter<'a, ($($T,)*)>| {
+                        intra_iter.fold(acc, |acc, [$($T,)*]| f(acc, ($($T::cast_ptr($T),)*)))
+                    };
+                    let intra_iter = core::mem::replace(&mut self.intra_iter, IntraArchetypeIter::<($($T,)*)>::unit());
+                    let mut acc = fold_archetype(init, intra_iter);
+                    while let Some(intra_iter) = self.next_archetype() {
+                        acc = fold_archetype(acc, intra_iter);
+                    }
+                    acc
+                }
         }
 
         impl<'a, $($T: QueryParam,)*> IntraArchetypeIter<'a, ($($T,)*)> {
+            /// Whether any param of the query filters rows or marks them as changed, these are constants so queries
+            /// without any tick work compile down to a loop that only offsets the component pointers
+            const FILTERS: bool = false $(|| $T::FILTERS)*;
+            const UPDATES: bool = false $(|| $T::UPDATES)*;
+
+            /// Iterates ``remaining`` rows of ``archetype`` starting at row ``start``
+            ///
+            /// Without ``Added`` or ``Changed`` fetches every row is returned so all of them are marked as changed here
+            /// in one fill of each column's changed ticks, even if the iterator is dropped before reaching them. Kept out
+            /// of line so the fill doesn't get inlined into the loops iterating the rows
+            #[inline(never)]
+            fn new(
+                archetype: &Archetype,
+                start: usize,
+                remaining: usize,
+                ptrs: [*mut u8; $N],
+                fetches: &'a [FetchType; $N],
+                last_run: u32,
+                change_tick: u32,
+            ) -> Self {
+                let (ticks, changed) = split_ticks(fetches, archetype);
+                let mut iter = Self {
+                    remaining,
+                    ptrs,
+                    ticks,
+                    changed,
+                    fetches: Some(fetches),
+                    last_run,
+                    change_tick,
+                    _p: PhantomData,
+                };
+                iter.offset(start);
+
+                if Self::UPDATES && !Self::FILTERS {
+                    for &ticks in iter.changed.iter().filter(|ticks| !ticks.is_null()) {
+                        unsafe { core::slice::from_raw_parts_mut(ticks, remaining) }.fill(change_tick);
+                    }
+                }
+                iter
+            }
+
             fn unit() -> Self {
                 Self {
                     remaining: 0,
                     ptrs: [0x0 as _; $N],
+                    ticks: [0x0 as _; $N],
+                    changed: [0x0 as _; $N],
+                    fetches: None,
+                    last_run: 0,
+                    change_tick: 0,
                     _p: PhantomData,
                 }
             }
+
+            /// Skips ``rows`` rows without checking or updating their ticks
+            #[allow(unused_assignments)]
+            fn offset(&mut self, rows: usize) {
+                let mut n = 0;
+                $(
+                    $T::offset_ptr(&mut self.ptrs[n], rows);
+                    n += 1;
+                )*
+
+                if Self::FILTERS {
+                    for ticks in self.ticks.iter_mut().filter(|ticks| !ticks.is_null()) {
+                        *ticks = unsafe { ticks.add(rows) };
+                    }
+                }
+                if Self::UPDATES {
+                    for ticks in self.changed.iter_mut().filter(|ticks| !ticks.is_null()) {
+                        *ticks = unsafe { ticks.add(rows) };
+                    }
+                }
+            }
+
+            /// ``Iterator::next`` for queries with ``Added`` or ``Changed`` fetches, rows are skipped until one matches
+            fn next_filtered(&mut self) -> Option<[*mut u8; $N]> {
+                loop {
+                    if self.remaining == 0 {
+                        return None;
+                    }
+                    self.remaining -= 1;
+
+                    let ptrs = self.ptrs;
+                    let ticks = self.ticks;
+                    let changed = self.changed;
+                    self.offset(1);
+
+                    let matches = self
+                        .fetches
+                        .unwrap()
+                        .iter()
+                        .zip(ticks.iter())
+                        .all(|(fetch, &ticks)| fetch.filter_ticks(ticks, self.last_run));
+                    if !matches {
+                        continue;
+                    }
+
+                    for &ticks in changed.iter().filter(|ticks| !ticks.is_null()) {
+                        unsafe { *ticks = self.change_tick };
+                    }
+                    return Some(ptrs);
+                }
+            }
         }
 
         impl<'a, $($T: QueryParam,)*> Iterator for IntraArchetypeIter<'a, ($($T,)*)> {
             type Item = [*mut u8; $N];
 
             #[allow(unused_assignments)]
+            #[inline(always)]
             fn next(&mut self) -> Option<Self::Item> {
+                if Self::FILTERS {
+                    return self.next_filtered();
+                }
+
                 if self.remaining == 0 {
                     return None;
                 }
                 self.remaining -= 1;
 
+                // Rows were already marked as changed by ``IntraArchetypeIter::new``
                 let ptrs = self.ptrs;
-
                 let mut n = 0;
                 $(
                     $T::offset_ptr(&mut self.ptrs[n], 1);
                     n += 1;
                 )*
-
                 Some(ptrs)
             }
         }
@@ -221,8 +532,13 @@ impl_query_tuple!(A 1);
 pub trait QueryParam: 'static {
     type Returns<'a>;
 
+    /// True for params that skip rows based on their ticks
+    const FILTERS: bool = false;
+    /// True for params that mark the rows they return as changed
+    const UPDATES: bool = false;
+
     fn fetch_type(world: &World) -> Option<FetchType>;
-    fn create_ptr(archetype: &Archetype, fetch: &FetchType) -> Option<*mut u8>;
+    fn create_ptr(world: &World, archetype: &Archetype, fetch: &FetchType) -> Option<*mut u8>;
     fn offset_ptr(ptr: &mut *mut u8, elements: usize);
     fn cast_ptr<'a>(ptr: *mut u8) -> Self::Returns<'a>;
 }
@@ -230,15 +546,17 @@ pub trait QueryParam: 'static {
 impl<T: Component> QueryParam for &'static mut T {
     type Returns<'a> = &'a mut T;
 
+    const UPDATES: bool = true;
+
     fn fetch_type(world: &World) -> Option<FetchType> {
         let id = *world.type_id_to_ecs_id.get(&TypeId::of::<T>())?;
         Some(FetchType::Mut(id))
     }
 
-    fn create_ptr(archetype: &Archetype, fetch: &FetchType) -> Option<*mut u8> {
+    fn create_ptr(_: &World, archetype: &Archetype, fetch: &FetchType) -> Option<*mut u8> {
         let &storage_idx = archetype.comp_lookup.get(&fetch.get_id().unwrap())?;
         let storage = unsafe { &mut *archetype.component_storages[storage_idx].1.get() };
-        unsafe { Some(storage.as_mut_ptr()) }
+        unsafe { Some(storage.data.as_mut_ptr()) }
     }
 
     fn offset_ptr(ptr: &mut *mut u8, elements: usize) {
@@ -257,10 +575,10 @@ impl<T: Component> QueryParam for &'static T {
         Some(FetchType::Immut(id))
     }
 
-    fn create_ptr(archetype: &Archetype, fetch: &FetchType) -> Option<*mut u8> {
+    fn create_ptr(_: &World, archetype: &Archetype, fetch: &FetchType) -> Option<*mut u8> {
         let &storage_idx = archetype.comp_lookup.get(&fetch.get_id().unwrap())?;
         let storage = unsafe { &*archetype.component_storages[storage_idx].1.get() };
-        unsafe { Some(storage.as_immut_ptr() as *mut u8) }
+        unsafe { Some(storage.data.as_immut_ptr() as *mut u8) }
     }
 
     fn offset_ptr(ptr: &mut *mut u8, elements: usize) {
@@ -272,6 +590,81 @@ impl<T: Component> QueryParam for &'static T {
     }
 }
 
+impl<T: Component> QueryParam for Option<&'static mut T> {
+    type Returns<'a> = Option<&'a mut T>;
+
+    const UPDATES: bool = true;
+
+    fn fetch_type(world: &World) -> Option<FetchType> {
+        match world.type_id_to_ecs_id.get(&TypeId::of::<T>()) {
+            Some(&id) => Some(FetchType::OptionalMut(id)),
+            // No entity can have a component that was never registered so every fetch will be None,
+            // FetchType::EcsId matches every archetype without borrowing anything
+            None => Some(FetchType::EcsId),
+        }
+    }
+
+    fn create_ptr(_: &World, archetype: &Archetype, fetch: &FetchType) -> Option<*mut u8> {
+        let storage_idx = match fetch {
+            FetchType::OptionalMut(id) => archetype.comp_lookup.get(id),
+            _ => None,
+        };
+        match storage_idx {
+            Some(&storage_idx) => {
+                let storage = unsafe { &mut *archetype.component_storages[storage_idx].1.get() };
+                unsafe { Some(storage.data.as_mut_ptr()) }
+            }
+            None => Some(core::ptr::null_mut()),
+        }
+    }
+
+    fn offset_ptr(ptr: &mut *mut u8, elements: usize) {
+        if !ptr.is_null() {
+            *ptr = unsafe { ((*ptr) as *mut T).add(elements) as *mut u8 };
+        }
+    }
+
+    fn cast_ptr<'a>(ptr: *mut u8) -> Self::Returns<'a> {
+        unsafe { (ptr as *mut T).as_mut() }
+    }
+}
+impl<T: Component> QueryParam for Option<&'static T> {
+    type Returns<'a> = Option<&'a T>;
+
+    fn fetch_type(world: &World) -> Option<FetchType> {
+        match world.type_id_to_ecs_id.get(&TypeId::of::<T>()) {
+            Some(&id) => Some(FetchType::OptionalImmut(id)),
+            // No entity can have a component that was never registered so every fetch will be None,
+            // FetchType::EcsId matches every archetype without borrowing anything
+            None => Some(FetchType::EcsId),
+        }
+    }
+
+    fn create_ptr(_: &World, archetype: &Archetype, fetch: &FetchType) -> Option<*mut u8> {
+        let storage_idx = match fetch {
+            FetchType::OptionalImmut(id) => archetype.comp_lookup.get(id),
+            _ => None,
+        };
+        match storage_idx {
+            Some(&storage_idx) => {
+                let storage = unsafe { &*archetype.component_storages[storage_idx].1.get() };
+                unsafe { Some(storage.data.as_immut_ptr() as *mut u8) }
+            }
+            None => Some(core::ptr::null_mut()),
+        }
+    }
+
+    fn offset_ptr(ptr: &mut *mut u8, elements: usize) {
+        if !ptr.is_null() {
+            *ptr = unsafe { ((*ptr) as *mut T).add(elements) as *mut u8 };
+        }
+    }
+
+    fn cast_ptr<'a>(ptr: *mut u8) -> Self::Returns<'a> {
+        unsafe { (ptr as *const T).as_ref() }
+    }
+}
+
 pub struct EcsIds;
 impl QueryParam for EcsIds {
     type Returns<'a> = EcsId;
@@ -280,7 +673,7 @@ impl QueryParam for EcsIds {
         Some(FetchType::EcsId)
     }
 
-    fn create_ptr(archetype: &Archetype, _: &FetchType) -> Option<*mut u8> {
+    fn create_ptr(_: &World, archetype: &Archetype, _: &FetchType) -> Option<*mut u8> {
         Some(archetype.entities.as_ptr() as *mut EcsId as *mut u8)
     }
 
@@ -292,3 +685,121 @@ impl QueryParam for EcsIds {
         unsafe { *(ptr as *mut EcsId) }
     }
 }
+
+/// Filters a query to only entities that have the component `T`, without borrowing it
+pub struct With<T: Component>(PhantomData<T>);
+impl<T: Component> QueryParam for With<T> {
+    type Returns<'a> = ();
+
+    fn fetch_type(world: &World) -> Option<FetchType> {
+        let id = *world.type_id_to_ecs_id.get(&TypeId::of::<T>())?;
+        Some(FetchType::With(id))
+    }
+
+    fn create_ptr(_: &World, archetype: &Archetype, fetch: &FetchType) -> Option<*mut u8> {
+        archetype.comp_lookup.get(&fetch.get_id().unwrap())?;
+        Some(NonNull::dangling().as_ptr())
+    }
+
+    fn offset_ptr(_: &mut *mut u8, _: usize) {}
+
+    fn cast_ptr<'a>(_: *mut u8) -> Self::Returns<'a> {}
+}
+
+/// Filters a query to only entities that do not have the component `T`
+pub struct Without<T: Component>(PhantomData<T>);
+impl<T: Component> QueryParam for Without<T> {
+    type Returns<'a> = ();
+
+    fn fetch_type(world: &World) -> Option<FetchType> {
+        match world.type_id_to_ecs_id.get(&TypeId::of::<T>()) {
+            Some(&id) => Some(FetchType::Without(id)),
+            // No entity can have a component that was never registered so there is nothing to filter out,
+            // FetchType::EcsId matches every archetype without borrowing anything
+            None => Some(FetchType::EcsId),
+        }
+    }
+
+    fn create_ptr(_: &World, archetype: &Archetype, fetch: &FetchType) -> Option<*mut u8> {
+        if let FetchType::Without(id) = fetch {
+            if archetype.comp_lookup.contains_key(id) {
+                return None;
+            }
+        }
+        Some(NonNull::dangling().as_ptr())
+    }
+
+    fn offset_ptr(_: &mut *mut u8, _: usize) {}
+
+    fn cast_ptr<'a>(_: *mut u8) -> Self::Returns<'a> {}
+}
+
+/// Filters a query to only entities that had the component `T` added after the query's last run tick,
+/// see ``StaticQuery::since``
+pub struct Added<T: Component>(PhantomData<T>);
+impl<T: Component> QueryParam for Added<T> {
+    type Returns<'a> = ();
+
+    const FILTERS: bool = true;
+
+    fn fetch_type(world: &World) -> Option<FetchType> {
+        let id = *world.type_id_to_ecs_id.get(&TypeId::of::<T>())?;
+        Some(FetchType::Added(id))
+    }
+
+    fn create_ptr(_: &World, archetype: &Archetype, fetch: &FetchType) -> Option<*mut u8> {
+        archetype.comp_lookup.get(&fetch.get_id().unwrap())?;
+        Some(NonNull::dangling().as_ptr())
+    }
+
+    fn offset_ptr(_: &mut *mut u8, _: usize) {}
+
+    fn cast_ptr<'a>(_: *mut u8) -> Self::Returns<'a> {}
+}
+
+/// Filters a query to only entities that had the component `T` added or mutably accessed after the query's last
+/// run tick, see ``StaticQuery::since``
+pub struct Changed<T: Component>(PhantomData<T>);
+impl<T: Component> QueryParam for Changed<T> {
+    type Returns<'a> = ();
+
+    const FILTERS: bool = true;
+
+    fn fetch_type(world: &World) -> Option<FetchType> {
+        let id = *world.type_id_to_ecs_id.get(&TypeId::of::<T>())?;
+        Some(FetchType::Changed(id))
+    }
+
+    fn create_ptr(_: &World, archetype: &Archetype, fetch: &FetchType) -> Option<*mut u8> {
+        archetype.comp_lookup.get(&fetch.get_id().unwrap())?;
+        Some(NonNull::dangling().as_ptr())
+    }
+
+    fn offset_ptr(_: &mut *mut u8, _: usize) {}
+
+    fn cast_ptr<'a>(_: *mut u8) -> Self::Returns<'a> {}
+}
+
+/// Fetches the resource ``T`` for every entity the query matches, the query doesn't match anything if the resource
+/// doesn't exist. See ``World::insert_resource``
+pub struct Resource<T: Component>(PhantomData<T>);
+impl<T: Component> QueryParam for Resource<T> {
+    type Returns<'a> = &'a T;
+
+    fn fetch_type(world: &World) -> Option<FetchType> {
+        let id = *world.type_id_to_ecs_id.get(&TypeId::of::<T>())?;
+        world.resource_ptr(id)?;
+        Some(FetchType::Resource(id))
+    }
+
+    fn create_ptr(world: &World, _: &Archetype, fetch: &FetchType) -> Option<*mut u8> {
+        let (ptr, _) = world.resource_ptr(fetch.get_id().unwrap())?;
+        Some(ptr)
+    }
+
+    fn offset_ptr(_: &mut *mut u8, _: usize) {}
+
+    fn cast_ptr<'a>(ptr: *mut u8) -> Self::Returns<'a> {
+        unsafe { &*(ptr as *const T) }
+    }
+}
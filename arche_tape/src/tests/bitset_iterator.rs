@@ -72,6 +72,27 @@ fn map_bitset() {
     assert!(matches!(bitset_iter.next(), None));
 }
 
+#[test]
+fn short_inverted_bitset() {
+    let invert_map: fn(usize) -> _ = |x| !x;
+    let map: fn(_) -> _ = |x| x;
+
+    let data1 = vec![0b1010_1011, 0b11];
+    let data2 = vec![0b0000_0011];
+
+    let mut bitset_iter = BitsetIterator::new(
+        [(data1.iter(), map), (data2.iter(), invert_map)],
+        usize::BITS * 2,
+    );
+
+    assert_eq!(bitset_iter.next(), Some(3));
+    assert_eq!(bitset_iter.next(), Some(5));
+    assert_eq!(bitset_iter.next(), Some(7));
+    assert_eq!(bitset_iter.next(), Some(64));
+    assert_eq!(bitset_iter.next(), Some(65));
+    assert!(matches!(bitset_iter.next(), None));
+}
+
 #[test]
 fn all_ones() {
     let map: fn(_) -> _ = |x| x;
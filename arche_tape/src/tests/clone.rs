@@ -0,0 +1,183 @@
+use crate::world::ComponentMeta;
+use crate::{CloneError, World};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Name(String);
+
+#[derive(Debug, PartialEq)]
+struct NotClone(u32);
+
+#[derive(Clone)]
+struct DropCounter(Arc<AtomicUsize>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+struct PanicOnClone;
+
+impl Clone for PanicOnClone {
+    fn clone(&self) -> Self {
+        panic!("PanicOnClone was cloned")
+    }
+}
+
+fn cloneable_world() -> World {
+    let mut world = World::new();
+    world.register_clone::<Name>();
+    world.register_clone::<u32>();
+    world
+}
+
+#[test]
+fn clone_entity() {
+    let mut world = cloneable_world();
+    let likes = world.spawn().build();
+    let apples = world.spawn().build();
+    let entity = world
+        .spawn()
+        .with(Name("bob".to_string()))
+        .with(10_u32)
+        .build();
+    world.add_component_dynamic(entity, likes);
+    world.add_pair(entity, 20_u32, apples);
+
+    let clone = world.clone_entity(entity);
+    assert_ne!(clone, entity);
+    assert_eq!(world.get::<Name>(clone), Some(&Name("bob".to_string())));
+    assert_eq!(world.get::<u32>(clone), Some(&10));
+    assert!(world.get_component_dynamic(clone, likes).is_some());
+    assert_eq!(world.targets::<u32>(clone), vec![apples]);
+
+    // The clone is independent of the original
+    world.get_mut::<Name>(clone).unwrap().0.push_str(" jr");
+    assert_eq!(world.get::<Name>(entity), Some(&Name("bob".to_string())));
+    world.despawn(entity);
+    assert_eq!(world.get::<Name>(clone), Some(&Name("bob jr".to_string())));
+    assert_eq!(world.query::<(&Name,)>().iter().count(), 1);
+}
+
+#[test]
+fn clone_entity_errors() {
+    let mut world = cloneable_world();
+    let entity = world
+        .spawn()
+        .with(Name("bob".to_string()))
+        .with(NotClone(1))
+        .build();
+    let not_clone_id = world.get_or_create_type_id_ecsid::<NotClone>();
+    assert_eq!(
+        world.try_clone_entity(entity),
+        Err(CloneError::NotCloneable(not_clone_id))
+    );
+    // Nothing was spawned by the failed clone
+    assert_eq!(world.query::<(&Name,)>().iter().count(), 1);
+
+    world.despawn(entity);
+    assert_eq!(
+        world.try_clone_entity(entity),
+        Err(CloneError::NoSuchEntity)
+    );
+}
+
+#[test]
+fn clone_entity_panic() {
+    let mut world = World::new();
+    // Registered first so that it is cloned before the panic
+    world.register_clone::<DropCounter>();
+    world.register_clone::<PanicOnClone>();
+    let drops = Arc::new(AtomicUsize::new(0));
+    let entity = world
+        .spawn()
+        .with(DropCounter(drops.clone()))
+        .with(PanicOnClone)
+        .build();
+
+    let cloned =
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| world.clone_entity(entity)));
+    assert!(cloned.is_err());
+    // The clone that was already made is dropped and nothing is spawned
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+    assert_eq!(world.query::<(&DropCounter,)>().iter().count(), 1);
+    assert_eq!(world.spawn().build().uindex(), entity.uindex() + 1);
+}
+
+#[test]
+fn clone_entity_with_component_meta() {
+    let mut world = World::new();
+    let entity = world.spawn().build();
+    let comp_id =
+        unsafe { world.spawn_with_component_meta(ComponentMeta::from_generic_clone::<Name>()) }
+            .build();
+    let data = Name("bob".to_string());
+    let mut data = core::mem::ManuallyDrop::new(data);
+    unsafe {
+        world.add_component_dynamic_with_data(entity, comp_id, &mut *data as *mut Name as *mut u8)
+    };
+
+    let clone = world.clone_entity(entity);
+    let ptr = world.get_component_dynamic(clone, comp_id).unwrap();
+    assert_eq!(unsafe { &*(ptr as *const Name) }, &Name("bob".to_string()));
+}
+
+#[test]
+fn try_clone() {
+    let mut world = cloneable_world();
+    let first = world.spawn().with(Name("first".to_string())).build();
+    let despawned = world.spawn().with(10_u32).build();
+    world.despawn(despawned);
+    let second = world
+        .spawn()
+        .with(Name("second".to_string()))
+        .with(20_u32)
+        .build();
+    world.increment_change_tick();
+
+    let mut clone = world.try_clone().unwrap();
+    assert_eq!(clone.change_tick(), world.change_tick());
+    assert_eq!(clone.despawned(), world.despawned());
+    assert!(!clone.is_alive(despawned));
+    assert_eq!(clone.get::<Name>(first), Some(&Name("first".to_string())));
+    assert_eq!(clone.get::<u32>(second), Some(&20));
+
+    // Both worlds reuse the same ids but are otherwise independent
+    let next = world.spawn().with(30_u32).build();
+    assert_eq!(clone.spawn().build(), next);
+    clone.get_mut::<Name>(first).unwrap().0.push_str(" clone");
+    clone.remove_component::<u32>(second);
+    assert_eq!(world.get::<Name>(first), Some(&Name("first".to_string())));
+    assert_eq!(world.get::<u32>(second), Some(&20));
+    assert_eq!(world.query::<(&u32,)>().iter().count(), 2);
+    assert_eq!(clone.query::<(&u32,)>().iter().count(), 0);
+}
+
+#[test]
+fn try_clone_errors() {
+    let mut world = cloneable_world();
+    world.spawn().with(Name("bob".to_string())).build();
+    let entity = world.spawn().with(NotClone(1)).build();
+    let not_clone_id = world.get_or_create_type_id_ecsid::<NotClone>();
+    assert!(matches!(
+        world.try_clone(),
+        Err(CloneError::NotCloneable(id)) if id == not_clone_id
+    ));
+
+    // Empty columns don't need to be cloneable
+    world.despawn(entity);
+    assert!(world.try_clone().is_ok());
+
+    let _query = world.query::<(&mut Name,)>();
+    assert!(matches!(world.try_clone(), Err(CloneError::Borrow(_))));
+}
+
+#[test]
+#[should_panic]
+fn clone_entity_not_cloneable() {
+    let mut world = World::new();
+    let entity = world.spawn().with(NotClone(1)).build();
+    world.clone_entity(entity);
+}
@@ -0,0 +1,154 @@
+use crate::{world::ComponentMeta, CommandBuffer, EcsIds, World};
+
+#[test]
+fn spawn_during_iteration() {
+    let mut world = World::new();
+    let mut buffer = CommandBuffer::new();
+
+    spawn!(&mut world, 10_u32);
+    spawn!(&mut world, 12_u32);
+
+    let mut spawned = Vec::new();
+    for (data,) in world.query::<(&u32,)>().iter() {
+        let entity = buffer
+            .spawn(&world)
+            .with(*data + 1)
+            .with(*data as u64)
+            .build();
+        spawned.push(entity);
+    }
+
+    world.apply(&mut buffer);
+    assert!(buffer.is_empty());
+
+    let mut q = world.query::<(&u32, &u64)>();
+    assert_eq!(q.get(spawned[0]), Some((&11, &10)));
+    assert_eq!(q.get(spawned[1]), Some((&13, &12)));
+}
+
+#[test]
+fn reserved_ids_are_unique() {
+    let mut world = World::new();
+    let mut buffer = CommandBuffer::new();
+
+    let e1 = buffer.spawn(&world).build();
+    let e2 = buffer.spawn(&world).build();
+    assert!(e1 != e2);
+
+    // Spawning flushes the reserved ids so that this entity doesn't reuse them
+    let e3 = spawn!(&mut world, 1_u32);
+    assert!(e3 != e1 && e3 != e2);
+    assert!(world.is_alive(e1));
+    assert!(world.is_alive(e2));
+
+    world.apply(&mut buffer);
+    assert!(world.is_alive(e1));
+    assert!(world.is_alive(e2));
+    assert!(world.is_alive(e3));
+}
+
+#[test]
+fn despawn_add_remove() {
+    let mut world = World::new();
+    let mut buffer = CommandBuffer::new();
+
+    let e1 = spawn!(&mut world, 10_u32, 12_u64);
+    let e2 = spawn!(&mut world, 14_u32);
+    let e3 = spawn!(&mut world, 16_u32);
+
+    for (entity, data) in world.query::<(EcsIds, &u32)>().iter() {
+        match *data {
+            10 => buffer.remove_component::<u64>(entity),
+            14 => buffer.add_component(entity, 1_u64),
+            _ => buffer.despawn(entity),
+        }
+    }
+    world.apply(&mut buffer);
+
+    assert!(world.has_component::<u64>(e1) == false);
+    assert!(world.has_component::<u64>(e2));
+    assert!(world.is_alive(e3) == false);
+}
+
+#[test]
+fn dynamic_commands() {
+    let mut world = World::new();
+    let mut buffer = CommandBuffer::new();
+
+    let tag = world.spawn().build();
+    let u32_id = unsafe {
+        world
+            .spawn_with_component_meta(ComponentMeta::from_generic::<u32>())
+            .build()
+    };
+    let e1 = world.spawn().with_dynamic(tag).build();
+
+    let e2 = unsafe {
+        buffer
+            .spawn(&world)
+            .with_dynamic(tag)
+            .with_dynamic_with_data(&mut 10_u32 as *mut u32 as *mut u8, u32_id)
+            .build()
+    };
+    buffer.remove_component_dynamic(e1, tag);
+    world.apply(&mut buffer);
+
+    let e1_archetype = world.get_entity_meta(e1).unwrap().instance_meta.archetype.0;
+    assert!(world.archetypes[e1_archetype].comp_ids.is_empty());
+    let ptr = world.get_component_dynamic(e2, u32_id).unwrap();
+    assert_eq!(unsafe { *(ptr as *const u32) }, 10);
+}
+
+#[test]
+fn drop_unapplied() {
+    use std::rc::Rc;
+
+    let world = World::new();
+    let mut buffer = CommandBuffer::new();
+    let rc = Rc::new(());
+
+    buffer.spawn(&world).with(0_u8).with(rc.clone()).build();
+    assert!(Rc::strong_count(&rc) == 2);
+    drop(buffer);
+    assert!(Rc::strong_count(&rc) == 1);
+}
+
+#[test]
+fn add_to_despawned_entity() {
+    use std::rc::Rc;
+
+    let mut world = World::new();
+    let mut buffer = CommandBuffer::new();
+    let rc = Rc::new(());
+
+    let entity = spawn!(&mut world, 1_u32);
+    buffer.despawn(entity);
+    buffer.add_component(entity, rc.clone());
+    world.apply(&mut buffer);
+
+    assert!(world.is_alive(entity) == false);
+    assert!(Rc::strong_count(&rc) == 1);
+}
+
+#[test]
+fn add_present_remove_missing() {
+    use std::rc::Rc;
+
+    let mut world = World::new();
+    let mut buffer = CommandBuffer::new();
+    let rc = Rc::new(());
+
+    let entity = spawn!(&mut world, 1_u32, rc.clone());
+    buffer.add_component(entity, rc.clone());
+    buffer.remove_component::<u64>(entity);
+    buffer.add_component(entity, 10_u64);
+    buffer.remove_component::<u64>(entity);
+    buffer.remove_component::<u64>(entity);
+    buffer.add_component(entity, 12_u16);
+    world.apply(&mut buffer);
+
+    // The duplicate Rc was dropped and the commands after the failed ones were still applied
+    assert!(Rc::strong_count(&rc) == 2);
+    assert_eq!(world.get::<u64>(entity), None);
+    assert_eq!(world.get::<u16>(entity), Some(&12));
+}
@@ -218,3 +218,113 @@ fn non_present_component_query() {
         .iter()
         .for_each(|_| panic!());
 }
+
+#[test]
+fn with_without_filter() {
+    unsafe {
+        let mut world = World::new();
+
+        let u32_id = world
+            .spawn_with_component_meta(ComponentMeta::from_generic::<u32>())
+            .build();
+        let tag = world.spawn().build();
+
+        world
+            .spawn()
+            .with_dynamic_with_data({ &mut 10_u32 } as *mut u32 as *mut u8, u32_id)
+            .with_dynamic(tag)
+            .build();
+        world
+            .spawn()
+            .with_dynamic_with_data({ &mut 15_u32 } as *mut u32 as *mut u8, u32_id)
+            .build();
+
+        let mut query = world.query_dynamic([FetchType::Immut(u32_id), FetchType::With(tag)]);
+        let mut checks = vec![10].into_iter();
+        for data in query.iter().map(|[ptr, _]| &*{ ptr as *mut u32 }) {
+            assert_eq!(checks.next().unwrap(), *data);
+        }
+        assert!(checks.next().is_none());
+
+        let mut query = world.query_dynamic([FetchType::Immut(u32_id), FetchType::Without(tag)]);
+        let mut checks = vec![15].into_iter();
+        for data in query.iter().map(|[ptr, _]| &*{ ptr as *mut u32 }) {
+            assert_eq!(checks.next().unwrap(), *data);
+        }
+        assert!(checks.next().is_none());
+    }
+}
+
+#[test]
+fn optional_fetch() {
+    unsafe {
+        let mut world = World::new();
+
+        let u32_id = world
+            .spawn_with_component_meta(ComponentMeta::from_generic::<u32>())
+            .build();
+        let u64_id = world
+            .spawn_with_component_meta(ComponentMeta::from_generic::<u64>())
+            .build();
+
+        world
+            .spawn()
+            .with_dynamic_with_data({ &mut 10_u32 } as *mut u32 as *mut u8, u32_id)
+            .with_dynamic_with_data({ &mut 12_u64 } as *mut u64 as *mut u8, u64_id)
+            .build();
+        world
+            .spawn()
+            .with_dynamic_with_data({ &mut 15_u32 } as *mut u32 as *mut u8, u32_id)
+            .build();
+
+        let mut query =
+            world.query_dynamic([FetchType::Immut(u32_id), FetchType::OptionalMut(u64_id)]);
+        let mut checks = vec![(10, Some(12)), (15, None)].into_iter();
+        for (left, right) in query
+            .iter()
+            .map(|[a, b]| (&*{ a as *mut u32 }, { b as *mut u64 }.as_mut()))
+        {
+            assert_eq!(checks.next().unwrap(), (*left, right.map(|right| *right)));
+        }
+        assert!(checks.next().is_none());
+    }
+}
+
+#[test]
+fn changed_filter() {
+    unsafe {
+        let mut world = World::new();
+
+        let u32_id = world
+            .spawn_with_component_meta(ComponentMeta::from_generic::<u32>())
+            .build();
+
+        world
+            .spawn()
+            .with_dynamic_with_data({ &mut 10_u32 } as *mut u32 as *mut u8, u32_id)
+            .build();
+        let b = world
+            .spawn()
+            .with_dynamic_with_data({ &mut 15_u32 } as *mut u32 as *mut u8, u32_id)
+            .build();
+        let last_run = world.increment_change_tick();
+        world.increment_change_tick();
+
+        *(world.get_component_mut_dynamic(b, u32_id).unwrap() as *mut u32) += 1;
+
+        let mut query = world
+            .query_dynamic([FetchType::Mut(u32_id), FetchType::Changed(u32_id)])
+            .since(last_run);
+        let mut checks = vec![16].into_iter();
+        for [ptr, _] in query.iter() {
+            assert_eq!(checks.next().unwrap(), *(ptr as *mut u32));
+        }
+        assert!(checks.next().is_none());
+        drop(query);
+
+        let mut query = world
+            .query_dynamic([FetchType::Added(u32_id)])
+            .since(last_run);
+        assert_eq!(query.iter().count(), 0);
+    }
+}
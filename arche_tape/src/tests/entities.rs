@@ -151,6 +151,31 @@ pub fn generation_wraps() {
     assert!(entities.despawned.len() == 0);
 }
 
+#[test]
+pub fn reserve_then_flush() {
+    let mut entities = Entities::new();
+
+    let entity = entities.spawn();
+    let entity2 = entities.spawn();
+    entities.despawn(entity);
+
+    let reserved = entities.reserve();
+    let reserved2 = entities.reserve();
+    assert_eq!(reserved, EcsId::new(0, 1));
+    assert_eq!(reserved2, EcsId::new(2, 0));
+    assert!(entities.is_alive(reserved));
+    assert!(entities.is_alive(reserved2));
+    assert!(entities.is_alive(entity) == false);
+
+    assert!(entities.flush_reserved() == vec![reserved, reserved2]);
+    assert!(entities.is_alive(reserved));
+    assert!(entities.is_alive(reserved2));
+    assert!(entities.is_alive(entity2));
+    assert!(entities.despawned.is_empty());
+    assert!(entities.generations.len() == 3);
+    assert_eq!(entities.spawn(), EcsId::new(3, 0));
+}
+
 #[test]
 pub fn build_with_zst() -> () {
     struct Zero;
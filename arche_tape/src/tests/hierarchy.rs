@@ -0,0 +1,206 @@
+use crate::{Children, CloneError, EcsId, Parent, World};
+
+/// Spawns ``root`` -> [a -> [c, d], b -> [e]]
+fn tree(world: &mut World) -> [EcsId; 6] {
+    let [root, a, b, c, d, e] = [(); 6].map(|_| world.spawn().build());
+    world.set_parent(a, root);
+    world.set_parent(b, root);
+    world.set_parent(c, a);
+    world.set_parent(d, a);
+    world.set_parent(e, b);
+    [root, a, b, c, d, e]
+}
+
+#[test]
+fn set_parent() {
+    let mut world = World::new();
+    let [root, a, b, c, d, _] = tree(&mut world);
+
+    assert_eq!(world.get::<Parent>(a).map(Parent::get), Some(root));
+    assert_eq!(world.get::<Parent>(root), None);
+    assert_eq!(&**world.get::<Children>(a).unwrap(), &[c, d]);
+    assert_eq!(world.get::<Children>(c), None);
+
+    world.set_parent(c, b);
+    assert_eq!(&**world.get::<Children>(a).unwrap(), &[d]);
+    assert_eq!(world.get::<Parent>(c).map(Parent::get), Some(b));
+    assert_eq!(world.get::<Children>(b).unwrap().last(), Some(&c));
+}
+
+#[test]
+fn remove_parent() {
+    let mut world = World::new();
+    let [root, _, b, _, _, e] = tree(&mut world);
+
+    assert_eq!(world.remove_parent(e), Some(b));
+    assert_eq!(world.remove_parent(e), None);
+    assert_eq!(world.get::<Parent>(e), None);
+    // Entities without children have no Children component
+    assert_eq!(world.get::<Children>(b), None);
+    assert_eq!(world.get::<Parent>(b).map(Parent::get), Some(root));
+}
+
+#[test]
+fn remove_parent_without_children() {
+    let mut world = World::new();
+    let [_, a, _, c, d, _] = tree(&mut world);
+
+    // A Parent that was added or left behind without the matching Children is detached as if it were
+    world.remove_component::<Children>(a);
+    assert_eq!(world.remove_parent(c), Some(a));
+    assert_eq!(world.get::<Parent>(c), None);
+    assert_eq!(world.get::<Parent>(d).map(Parent::get), Some(a));
+}
+
+#[test]
+#[should_panic(expected = "descendant of itself")]
+fn set_parent_cycle() {
+    let mut world = World::new();
+    let [root, _, _, c, _, _] = tree(&mut world);
+    world.set_parent(root, c);
+}
+
+#[test]
+fn descendants() {
+    let mut world = World::new();
+    let [root, a, b, c, d, e] = tree(&mut world);
+
+    let depth_first = world.descendants_depth_first(root).collect::<Vec<_>>();
+    assert_eq!(depth_first, vec![a, c, d, b, e]);
+    let breadth_first = world.descendants_breadth_first(root).collect::<Vec<_>>();
+    assert_eq!(breadth_first, vec![a, b, c, d, e]);
+    assert_eq!(world.descendants_depth_first(e).count(), 0);
+}
+
+#[test]
+fn descendants_unregistered() {
+    let mut world = World::new();
+    let entity = world.spawn().build();
+    assert_eq!(world.descendants_depth_first(entity).count(), 0);
+    assert_eq!(world.descendants_breadth_first(entity).count(), 0);
+}
+
+#[test]
+#[should_panic(expected = "is already borrowed")]
+fn descendants_conflict() {
+    let mut world = World::new();
+    let [root, ..] = tree(&mut world);
+
+    let _query = world.query::<(&mut Children,)>();
+    world.descendants_depth_first(root);
+}
+
+#[test]
+fn despawn() {
+    let mut world = World::new();
+    let [root, a, b, c, d, _] = tree(&mut world);
+
+    world.despawn(a);
+    assert_eq!(&**world.get::<Children>(root).unwrap(), &[b]);
+    assert_eq!(world.get::<Parent>(c), None);
+    assert_eq!(world.get::<Parent>(d), None);
+    assert!(world.is_alive(c) && world.is_alive(d));
+}
+
+#[test]
+fn despawn_with_detached_children() {
+    let mut world = World::new();
+    let [root, a, _, c, d, _] = tree(&mut world);
+
+    // Both are still in the children of `a` but `c` has no parent and `d` points at `root`
+    world.remove_component::<Parent>(c);
+    let parent = *world.get::<Parent>(a).unwrap();
+    world.remove_component::<Parent>(d);
+    world.add_component(d, parent);
+
+    world.despawn(a);
+    assert_eq!(world.get::<Parent>(c), None);
+    assert_eq!(world.get::<Parent>(d).map(Parent::get), Some(root));
+}
+
+#[test]
+fn despawn_recursive() {
+    let mut world = World::new();
+    let [root, a, b, c, d, e] = tree(&mut world);
+    let other = world.spawn().build();
+    world.set_parent(other, b);
+
+    assert!(world.despawn_recursive(a));
+    assert!(!world.despawn_recursive(a));
+    assert!([a, c, d].iter().all(|&entity| !world.is_alive(entity)));
+    assert_eq!(&**world.get::<Children>(root).unwrap(), &[b]);
+
+    world.despawn_recursive(root);
+    assert!(
+        [root, b, e, other]
+            .iter()
+            .all(|&entity| !world.is_alive(entity))
+    );
+    assert_eq!(world.query::<(&Parent,)>().iter().count(), 0);
+    assert_eq!(world.query::<(&Children,)>().iter().count(), 0);
+}
+
+#[test]
+fn merge_from() {
+    let mut world = World::new();
+    let other_root = world.spawn().build();
+    let mut scratch = World::new();
+    let [root, a, b, c, d, e] = tree(&mut scratch);
+
+    let entity_map = world.merge_from(scratch);
+    let [root, a, b, c, d, e] = [root, a, b, c, d, e].map(|entity| entity_map.get(entity).unwrap());
+    let descendants = world.descendants_depth_first(root).collect::<Vec<_>>();
+    assert_eq!(descendants, vec![a, c, d, b, e]);
+    assert_eq!(world.get::<Parent>(e).map(Parent::get), Some(b));
+
+    world.set_parent(root, other_root);
+    assert_eq!(world.descendants_breadth_first(other_root).count(), 6);
+}
+
+#[test]
+fn clone_entity() {
+    let mut world = World::new();
+    let [root, a, _, c, d, _] = tree(&mut world);
+    world.register_clone::<Parent>();
+    world.register_clone::<Children>();
+
+    // A clone of a child is another child of the same parent
+    let clone = world.clone_entity(c);
+    assert_eq!(world.get::<Parent>(clone).map(Parent::get), Some(a));
+    assert_eq!(&**world.get::<Children>(a).unwrap(), &[c, d, clone]);
+    assert_eq!(world.remove_parent(clone), Some(a));
+    assert_eq!(&**world.get::<Children>(a).unwrap(), &[c, d]);
+
+    // Cloning an entity with children clones its whole subtree
+    let clone = world.clone_entity(a);
+    assert_eq!(world.get::<Parent>(clone).map(Parent::get), Some(root));
+    assert_eq!(&**world.get::<Children>(a).unwrap(), &[c, d]);
+    let cloned_children = world.get::<Children>(clone).unwrap().to_vec();
+    assert_eq!(cloned_children.len(), 2);
+    assert!(cloned_children.iter().all(|child| ![c, d].contains(child)));
+    for child in cloned_children {
+        assert_eq!(world.get::<Parent>(child).map(Parent::get), Some(clone));
+    }
+
+    let clone = world.clone_entity(root);
+    assert_eq!(world.get::<Parent>(clone), None);
+    assert_eq!(world.descendants_depth_first(clone).count(), 8);
+    assert_eq!(world.descendants_depth_first(root).count(), 8);
+}
+
+#[test]
+fn clone_entity_not_cloneable_descendant() {
+    let mut world = World::new();
+    let [root, a, _, _, _, e] = tree(&mut world);
+    world.register_clone::<Parent>();
+    world.register_clone::<Children>();
+    world.add_component(e, 10_u32);
+
+    let u32_id = world.get_or_create_type_id_ecsid::<u32>();
+    assert_eq!(
+        world.try_clone_entity(root),
+        Err(CloneError::NotCloneable(u32_id))
+    );
+    assert_eq!(world.query::<(&Parent,)>().iter().count(), 5);
+    assert!(world.try_clone_entity(a).is_ok());
+}
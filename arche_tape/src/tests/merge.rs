@@ -0,0 +1,97 @@
+use crate::{EcsId, EntityMap, MapEntities, World};
+
+#[derive(Debug, PartialEq)]
+struct Target(EcsId);
+
+impl MapEntities for Target {
+    fn map_entities(&mut self, entity_map: &EntityMap) {
+        self.0 = entity_map.get(self.0).unwrap();
+    }
+}
+
+#[test]
+fn merge_from() {
+    let mut world = World::new();
+    let existing = spawn!(&mut world, 10_u32);
+
+    // Registering u64 first gives u32 a different id in the scratch world
+    let mut scratch = World::new();
+    scratch.get_or_create_type_id_ecsid::<u64>();
+    let first = spawn!(&mut scratch, 20_u32, 1_u64);
+    let second = spawn!(&mut scratch, 30_u32);
+    let empty = spawn!(&mut scratch);
+
+    let entity_map = world.merge_from(scratch);
+    let [first, second, empty] =
+        [first, second, empty].map(|entity| entity_map.get(entity).unwrap());
+    assert!(
+        [existing, first, second, empty]
+            .iter()
+            .all(|&entity| world.is_alive(entity))
+    );
+    assert_eq!(world.get::<u32>(first), Some(&20));
+    assert_eq!(world.get::<u64>(first), Some(&1));
+    assert_eq!(world.get::<u32>(second), Some(&30));
+    assert_eq!(world.get::<u32>(empty), None);
+
+    // Rows were appended to the existing archetype for u32
+    let nums = world
+        .query::<(&u32,)>()
+        .iter()
+        .map(|(&num,)| num)
+        .collect::<Vec<_>>();
+    assert_eq!(nums, vec![10, 30, 20]);
+}
+
+#[test]
+fn merge_from_fresh_ids() {
+    let mut world = World::new();
+    let existing = spawn!(&mut world, 10_u32);
+    let mut scratch = World::new();
+    let other = spawn!(&mut scratch, 20_u32);
+    // Both worlds use the same id for different entities
+    assert_eq!(other, existing);
+
+    let entity_map = world.merge_from(scratch);
+    let other = entity_map.get(other).unwrap();
+    assert_ne!(other, existing);
+    assert_eq!(world.get::<u32>(existing), Some(&10));
+    assert_eq!(world.get::<u32>(other), Some(&20));
+}
+
+#[test]
+fn merge_from_map_entities() {
+    let mut world = World::new();
+    let mut scratch = World::new();
+    scratch.register_map_entities::<Target>();
+    let target = spawn!(&mut scratch);
+    let entity = spawn!(&mut scratch, Target(target));
+    let likes = spawn!(&mut scratch);
+    scratch.add_component_dynamic(entity, likes);
+    scratch.add_component_dynamic(entity, EcsId::pair(likes, target));
+
+    let entity_map = world.merge_from(scratch);
+    let [target, entity, likes] =
+        [target, entity, likes].map(|entity| entity_map.get(entity).unwrap());
+    assert_eq!(world.get::<Target>(entity), Some(&Target(target)));
+    assert!(world.get_component_dynamic(entity, likes).is_some());
+    assert_eq!(world.targets_dynamic(entity, likes), vec![target]);
+}
+
+#[test]
+fn merge_from_ticks() {
+    let mut world = World::new();
+    world.increment_change_tick();
+    world.increment_change_tick();
+    let last_run = world.change_tick() - 1;
+    let mut scratch = World::new();
+    spawn!(&mut scratch, 20_u32);
+
+    world.merge_from(scratch);
+    let added = world
+        .query::<(crate::Added<u32>,)>()
+        .since(last_run)
+        .iter()
+        .count();
+    assert_eq!(added, 1);
+}
@@ -1,4 +1,4 @@
-use crate::{EcsId, EcsIds, StaticQuery, World};
+use crate::{Added, BorrowError, Changed, EcsId, EcsIds, FetchType, StaticQuery, With, Without, World};
 
 #[test]
 fn for_each_mut() {
@@ -68,6 +68,25 @@ fn for_each_multi_archetype_iterator() {
     assert!(checks.next().is_none());
 }
 
+#[test]
+fn for_each_after_next() {
+    let mut world = World::new();
+
+    spawn!(&mut world, 10_u32, 12_u64);
+    spawn!(&mut world, 15_u32, 14_u64);
+    spawn!(&mut world, 11_u32, 12_u64, 99_u128);
+    spawn!(&mut world, 16_u32, 14_u64, 99_u128);
+
+    let mut query = world.query::<(&mut u32,)>();
+    let mut iter = query.iter();
+    assert_eq!(*iter.next().unwrap().0, 10);
+
+    // The rest of the first archetype is folded before moving on to the next one
+    let mut rest = Vec::new();
+    iter.for_each(|(n,)| rest.push(*n));
+    assert_eq!(rest, vec![15, 11, 16]);
+}
+
 #[test]
 fn query_param_in_func() {
     let mut world = World::new();
@@ -124,3 +143,317 @@ fn non_present_component_query() {
     world.query::<(&mut u32,)>().iter().for_each(|_| panic!());
     world.query::<(&u32,)>().iter().for_each(|_| panic!());
 }
+
+#[test]
+fn with_filter() {
+    let mut world = World::new();
+
+    spawn!(&mut world, 10_u32, 12_u64);
+    spawn!(&mut world, 15_u32);
+    spawn!(&mut world, 20_u32, 16_u64, 99_u128);
+
+    let mut checks = vec![10, 20].into_iter();
+    for (left, ()) in world.query::<(&u32, With<u64>)>().iter() {
+        assert_eq!(*left, checks.next().unwrap());
+    }
+    assert!(checks.next().is_none());
+
+    // With doesn't take a lock so the component can still be borrowed mutably
+    let mut q = world.query::<(&mut u64, With<u64>)>();
+    assert_eq!(q.iter().count(), 2);
+}
+
+#[test]
+fn without_filter() {
+    let mut world = World::new();
+
+    let a = spawn!(&mut world, 10_u32, 12_u64);
+    let b = spawn!(&mut world, 15_u32);
+    let c = spawn!(&mut world, 20_u32, 99_u128);
+
+    let mut checks = vec![15, 20].into_iter();
+    for (left, ()) in world.query::<(&u32, Without<u64>)>().iter() {
+        assert_eq!(*left, checks.next().unwrap());
+    }
+    assert!(checks.next().is_none());
+
+    let mut q = world.query::<(&u32, Without<u64>, Without<u128>)>();
+    assert_eq!(q.get(a), None);
+    assert_eq!(q.get(b), Some((&15, (), ())));
+    assert_eq!(q.get(c), None);
+}
+
+#[test]
+fn without_unregistered_component() {
+    struct Unused;
+    let mut world = World::new();
+
+    spawn!(&mut world, 10_u32);
+    spawn!(&mut world, 15_u32, 12_u64);
+
+    assert_eq!(world.query::<(&u32, Without<Unused>)>().iter().count(), 2);
+    assert_eq!(world.query::<(&u32, With<Unused>)>().iter().count(), 0);
+}
+
+#[test]
+fn optional_fetch() {
+    let mut world = World::new();
+
+    let a = spawn!(&mut world, 10_u32, 12_u64);
+    let b = spawn!(&mut world, 15_u32);
+    spawn!(&mut world, 20_u32, 16_u64, 99_u128);
+    spawn!(&mut world, 14_u64);
+
+    let mut checks = vec![(10, Some(12)), (15, None), (20, Some(16))].into_iter();
+    for (left, right) in world.query::<(&u32, Option<&mut u64>)>().iter() {
+        assert_eq!(checks.next().unwrap(), (*left, right.as_deref().copied()));
+        if let Some(right) = right {
+            *right += 1;
+        }
+    }
+    assert!(checks.next().is_none());
+
+    let mut q = world.query::<(&u32, Option<&u64>)>();
+    assert_eq!(q.get(a), Some((&10, Some(&13))));
+    assert_eq!(q.get(b), Some((&15, None)));
+}
+
+#[test]
+fn optional_unregistered_component() {
+    struct Unused;
+    let mut world = World::new();
+
+    spawn!(&mut world, 10_u32);
+
+    let mut ran = false;
+    for (left, right) in world.query::<(&u32, Option<&Unused>)>().iter() {
+        assert_eq!(*left, 10);
+        assert!(right.is_none());
+        ran = true;
+    }
+    assert!(ran);
+}
+
+#[test]
+fn added_changed_filters() {
+    let mut world = World::new();
+
+    let a = spawn!(&mut world, 10_u32, 12_u64);
+    let b = spawn!(&mut world, 15_u32);
+    assert_eq!(world.query::<(&u32, Added<u32>)>().iter().count(), 2);
+
+    let last_run = world.change_tick();
+    world.increment_change_tick();
+    assert_eq!(
+        world
+            .query::<(&u32, Added<u32>)>()
+            .since(last_run)
+            .iter()
+            .count(),
+        0
+    );
+    assert_eq!(
+        world
+            .query::<(&u32, Changed<u32>)>()
+            .since(last_run)
+            .iter()
+            .count(),
+        0
+    );
+
+    let c = spawn!(&mut world, 20_u32);
+    *world.get_mut::<u32>(b).unwrap() += 1;
+    for (data, _) in world.query::<(&mut u64, With<u32>)>().iter() {
+        *data += 1;
+    }
+
+    let mut checks = vec![20].into_iter();
+    for (data, _) in world.query::<(&u32, Added<u32>)>().since(last_run).iter() {
+        assert_eq!(*data, checks.next().unwrap());
+    }
+    assert!(checks.next().is_none());
+
+    let mut checks = vec![16, 20].into_iter();
+    for (data, _) in world.query::<(&u32, Changed<u32>)>().since(last_run).iter() {
+        assert_eq!(*data, checks.next().unwrap());
+    }
+    assert!(checks.next().is_none());
+
+    let mut q = world.query::<(EcsIds, Changed<u64>)>().since(last_run);
+    assert_eq!(q.get(a), Some((a, ())));
+    assert_eq!(q.get(b), None);
+    assert_eq!(q.get(c), None);
+}
+
+#[test]
+fn changed_filter_on_mut_fetch() {
+    let mut world = World::new();
+
+    spawn!(&mut world, 10_u32);
+    spawn!(&mut world, 15_u32);
+    let last_run = world.increment_change_tick();
+
+    world.increment_change_tick();
+    for (data,) in world.query::<(&mut u32,)>().iter() {
+        if *data == 15 {
+            *data += 1;
+        }
+    }
+
+    // Filters are checked before the row is marked as changed by the `&mut u32` fetch
+    let mut checks = vec![10, 16].into_iter();
+    for (data, _) in world
+        .query::<(&mut u32, Changed<u32>)>()
+        .since(last_run)
+        .iter()
+    {
+        assert_eq!(*data, checks.next().unwrap());
+    }
+    assert!(checks.next().is_none());
+
+    let last_run = world.increment_change_tick();
+    assert_eq!(
+        world
+            .query::<(&u32, Changed<u32>)>()
+            .since(last_run)
+            .iter()
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn optional_mut_marks_changed() {
+    let mut world = World::new();
+
+    let a = spawn!(&mut world, 10_u32, 1_u64);
+    let b = spawn!(&mut world, 2_u64);
+    let last_run = world.increment_change_tick();
+    world.increment_change_tick();
+
+    // Only the archetype with a `u32` column has ticks to mark as changed
+    assert_eq!(world.query::<(&u64, Option<&mut u32>)>().iter().count(), 2);
+    let mut q = world.query::<(EcsIds, Changed<u32>)>().since(last_run);
+    assert_eq!(q.iter().map(|(id, _)| id).collect::<Vec<_>>(), vec![a]);
+    let mut q = world.query::<(EcsIds, Changed<u64>)>().since(last_run);
+    assert_eq!(q.get(b), None);
+}
+
+#[test]
+fn ticks_follow_moved_rows() {
+    let mut world = World::new();
+
+    let a = spawn!(&mut world, 10_u32);
+    let b = spawn!(&mut world, 15_u32);
+    let c = spawn!(&mut world, 20_u32);
+    let last_run = world.change_tick();
+    world.increment_change_tick();
+
+    *world.get_mut::<u32>(c).unwrap() += 1;
+    // Moving `a` swaps `c` into its row and removing from `b` swaps it back again
+    world.add_component(a, 1_u64);
+    world.remove_component::<u32>(b);
+
+    let mut q = world.query::<(&u32, Changed<u32>)>().since(last_run);
+    assert_eq!(q.get(a), None);
+    assert_eq!(q.get(c), Some((&21, ())));
+
+    let mut q = world.query::<(EcsIds, Added<u64>)>().since(last_run);
+    assert_eq!(q.get(a), Some((a, ())));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_iter() {
+    use rayon::iter::ParallelIterator;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    let mut world = World::new();
+    let spawned = world
+        .spawn_batch((0..5000_u64).map(|n| (n, n as u32)))
+        .to_vec();
+    world.spawn_batch((0..100_u64).map(|n| (n,)));
+    let last_run = world.change_tick();
+    world.increment_change_tick();
+
+    world
+        .query::<(&mut u64, &u32)>()
+        .par_for_each(|(n, m)| *n += *m as u64);
+    for (n, &entity) in spawned.iter().enumerate() {
+        assert_eq!(*world.get::<u64>(entity).unwrap(), n as u64 * 2);
+    }
+
+    let sum = AtomicU64::new(0);
+    world.query::<(&u64,)>().par_for_each(|(n,)| {
+        sum.fetch_add(*n, Ordering::Relaxed);
+    });
+    let expected = (0..5000).map(|n| n * 2).sum::<u64>() + (0..100).sum::<u64>();
+    assert_eq!(sum.into_inner(), expected);
+
+    let mut query = world.query::<(EcsIds, Changed<u64>)>().since(last_run);
+    assert_eq!(query.par_iter().count(), 5000);
+}
+
+#[test]
+fn try_query_aliased() {
+    let mut world = World::new();
+    spawn!(&mut world, 10_u32, 12_u64);
+    let u32_id = world.get_or_create_type_id_ecsid::<u32>();
+
+    assert_eq!(
+        world.try_query::<(&mut u32, &u32)>().err(),
+        Some(BorrowError::Aliased(u32_id))
+    );
+    assert_eq!(
+        world
+            .try_query::<(&u64, Option<&mut u32>, &mut u32)>()
+            .err(),
+        Some(BorrowError::Aliased(u32_id))
+    );
+    assert!(world.try_query::<(&u32, &u32, Changed<u32>)>().is_ok());
+    assert!(world.try_query::<(&mut u32, Changed<u32>)>().is_ok());
+}
+
+#[test]
+fn try_query_conflict() {
+    let mut world = World::new();
+    spawn!(&mut world, 10_u32, 12_u64);
+    let u32_id = world.get_or_create_type_id_ecsid::<u32>();
+
+    let query = world.query::<(&mut u32,)>();
+    let err = world.try_query::<(&u64, &u32)>().err().unwrap();
+    assert_eq!(err, BorrowError::Conflict(u32_id));
+    assert_eq!(err.component(), u32_id);
+    assert!(world.try_query::<(&mut u64,)>().is_ok());
+    drop(query);
+
+    let query = world.query::<(&u32,)>();
+    assert!(world.try_query::<(&u32,)>().is_ok());
+    assert_eq!(
+        world.try_query::<(Option<&mut u32>,)>().err(),
+        Some(BorrowError::Conflict(u32_id))
+    );
+    drop(query);
+
+    assert!(world.try_query::<(&mut u32,)>().is_ok());
+}
+
+#[test]
+#[should_panic(expected = "is already borrowed")]
+fn query_conflict() {
+    let mut world = World::new();
+    spawn!(&mut world, 10_u32);
+
+    let _query = world.query::<(&mut u32,)>();
+    world.query::<(&u32,)>();
+}
+
+#[test]
+#[should_panic(expected = "is borrowed mutably and borrowed again by the same query")]
+fn query_dynamic_aliased() {
+    let mut world = World::new();
+    spawn!(&mut world, 10_u32);
+    let u32_id = world.get_or_create_type_id_ecsid::<u32>();
+
+    world.query_dynamic([FetchType::Mut(u32_id), FetchType::Immut(u32_id)]);
+}
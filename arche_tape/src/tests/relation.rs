@@ -0,0 +1,164 @@
+use crate::{EcsId, FetchType, World};
+
+#[derive(Debug, PartialEq)]
+struct Likes(u32);
+
+#[test]
+fn pair_id() {
+    let mut world = World::new();
+    let relation = world.spawn().build();
+    let target = world.spawn().build();
+
+    let pair = EcsId::pair(relation, target);
+    assert!(pair.is_pair());
+    assert!(!relation.is_pair());
+    assert_ne!(pair, EcsId::pair(target, relation));
+    assert_eq!(pair, EcsId::pair(relation, target));
+    // Pairs are components, never entities
+    assert!(!world.is_alive(pair));
+}
+
+#[test]
+fn add_pair_dynamic() {
+    let mut world = World::new();
+    let likes = world.spawn().build();
+    let apples = world.spawn().build();
+    let pears = world.spawn().build();
+    let entity = world.spawn().build();
+
+    world.add_component_dynamic(entity, EcsId::pair(likes, apples));
+    world.add_component_dynamic(entity, EcsId::pair(likes, pears));
+    assert_eq!(world.targets_dynamic(entity, likes), vec![apples, pears]);
+    assert_eq!(world.targets_dynamic(entity, apples), vec![]);
+
+    world.remove_component_dynamic(entity, EcsId::pair(likes, apples));
+    assert_eq!(world.targets_dynamic(entity, likes), vec![pears]);
+}
+
+#[test]
+fn add_pair() {
+    let mut world = World::new();
+    let apples = world.spawn().build();
+    let pears = world.spawn().build();
+    let entity = world.spawn().build();
+
+    world.add_pair(entity, Likes(1), apples);
+    world.add_pair(entity, Likes(2), pears);
+    assert!(world.try_add_pair(entity, Likes(3), pears).is_err());
+    assert_eq!(world.targets::<Likes>(entity), vec![apples, pears]);
+
+    let pair = world.pair_id::<Likes>(pears);
+    let mut query = world.query_dynamic([FetchType::Immut(pair)]);
+    let data = query
+        .iter()
+        .map(|[likes]| unsafe { (*(likes as *mut Likes)).0 })
+        .collect::<Vec<_>>();
+    assert_eq!(data, vec![2]);
+    drop(query);
+
+    world.remove_pair::<Likes>(entity, apples);
+    assert_eq!(world.targets::<Likes>(entity), vec![pears]);
+    assert!(world.try_remove_pair::<Likes>(entity, apples).is_err());
+}
+
+#[test]
+fn query_wildcard() {
+    let mut world = World::new();
+    let likes = world.spawn().build();
+    let apples = world.spawn().build();
+    let pears = world.spawn().build();
+
+    let first = world.spawn().build();
+    world.add_component_dynamic(first, EcsId::pair(likes, apples));
+    let second = world.spawn().build();
+    world.add_component_dynamic(second, EcsId::pair(likes, pears));
+    let third = world.spawn().build();
+    world.add_component_dynamic(third, EcsId::pair(apples, pears));
+
+    let wildcard = EcsId::pair(likes, EcsId::WILDCARD);
+    let mut query = world.query_dynamic([FetchType::EcsId, FetchType::With(wildcard)]);
+    let liked = query
+        .iter()
+        .map(|[entity, _]| unsafe { *(entity as *mut EcsId) })
+        .collect::<Vec<_>>();
+    assert_eq!(liked, vec![first, second]);
+    drop(query);
+
+    let mut query = world.query_dynamic([
+        FetchType::EcsId,
+        FetchType::With(EcsId::pair(apples, EcsId::WILDCARD)),
+        FetchType::Without(wildcard),
+    ]);
+    let others = query
+        .iter()
+        .map(|[entity, _, _]| unsafe { *(entity as *mut EcsId) })
+        .collect::<Vec<_>>();
+    assert_eq!(others, vec![third]);
+}
+
+#[test]
+fn despawn_target() {
+    let mut world = World::new();
+    let apples = world.spawn().build();
+    let pears = world.spawn().build();
+    let entity = world.spawn().with(10_u32).build();
+    world.add_pair(entity, Likes(1), apples);
+    world.add_pair(entity, Likes(2), pears);
+
+    world.despawn(apples);
+    assert_eq!(world.targets::<Likes>(entity), vec![pears]);
+    assert_eq!(world.get::<u32>(entity), Some(&10));
+
+    // A new entity reusing the target's index doesn't inherit its pairs
+    let reused = world.spawn().build();
+    assert_eq!(reused.uindex(), apples.uindex());
+    assert_eq!(world.targets::<Likes>(entity), vec![pears]);
+    world.add_pair(entity, Likes(3), reused);
+    let targets = world.targets::<Likes>(entity);
+    assert!(targets.len() == 2 && targets.contains(&pears) && targets.contains(&reused));
+}
+
+#[test]
+fn despawn_relation() {
+    let mut world = World::new();
+    let likes = world.spawn().build();
+    let apples = world.spawn().build();
+    let entity = world.spawn().with(10_u32).build();
+    world.add_component_dynamic(entity, EcsId::pair(likes, apples));
+
+    world.despawn(likes);
+    assert_eq!(world.targets_dynamic(entity, likes), vec![]);
+    assert_eq!(world.get::<u32>(entity), Some(&10));
+    let wildcard = EcsId::pair(likes, EcsId::WILDCARD);
+    let mut query = world.query_dynamic([FetchType::With(wildcard)]);
+    assert_eq!(query.iter().count(), 0);
+}
+
+#[test]
+fn despawn_unrelated() {
+    let mut world = World::new();
+    let apples = world.spawn().build();
+    let entity = world.spawn().with(10_u32).build();
+    world.add_pair(entity, Likes(1), apples);
+
+    let other = world.spawn().with(12_u32).build();
+    world.despawn(other);
+    assert_eq!(world.targets::<Likes>(entity), vec![apples]);
+    assert_eq!(world.archetype_bitset.pair_ids_of(other.uindex()), &[]);
+    assert_eq!(world.archetype_bitset.pair_ids_of(apples.uindex()).len(), 1);
+
+    world.despawn(apples);
+    assert_eq!(world.archetype_bitset.pair_ids_of(apples.uindex()), &[]);
+}
+
+#[test]
+#[should_panic(expected = "entity index limit")]
+fn pair_relation_out_of_bounds() {
+    EcsId::pair(EcsId::new(u32::MAX - 1, 0), EcsId::new(0, 0));
+}
+
+#[test]
+#[should_panic(expected = "entity index limit")]
+fn pair_target_out_of_bounds() {
+    EcsId::pair(EcsId::new(0, 0), EcsId::new(u32::MAX, 1));
+}
@@ -0,0 +1,158 @@
+use crate::{Changed, EcsIds, FetchType, Resource, Schedule, StaticQuery, World};
+
+#[test]
+fn insert_resource() {
+    let mut world = World::new();
+    assert!(world.resource::<u32>().is_none());
+    assert_eq!(world.resource_entity(), None);
+
+    assert_eq!(world.insert_resource(10_u32), None);
+    assert_eq!(world.insert_resource(12_u64), None);
+    assert_eq!(*world.resource::<u32>().unwrap(), 10);
+    assert_eq!(*world.resource::<u64>().unwrap(), 12);
+
+    assert_eq!(world.insert_resource(15_u32), Some(10));
+    assert_eq!(*world.resource::<u32>().unwrap(), 15);
+
+    // Resources are components on the resource entity
+    let entity = world.resource_entity().unwrap();
+    assert_eq!(world.get::<u32>(entity), Some(&15));
+
+    assert_eq!(world.remove_resource::<u32>(), Some(15));
+    assert_eq!(world.remove_resource::<u32>(), None);
+    assert!(world.resource::<u32>().is_none());
+    assert_eq!(*world.resource::<u64>().unwrap(), 12);
+}
+
+#[test]
+fn despawn_resource_entity() {
+    let mut world = World::new();
+    world.insert_resource(10_u32);
+    assert!(!world.despawn(world.resource_entity().unwrap()));
+    assert_eq!(*world.resource::<u32>().unwrap(), 10);
+}
+
+#[test]
+fn merge_drops_resources() {
+    let mut world = World::new();
+    world.insert_resource(10_u32);
+    let mut other = World::new();
+    other.insert_resource(12_u32);
+    other.insert_resource(1_u64);
+    let other_resources = other.resource_entity().unwrap();
+    let entity = other.spawn().with(15_u32).build();
+
+    let entity_map = world.merge_from(other);
+    assert_eq!(entity_map.get(other_resources), None);
+    assert_eq!(*world.resource::<u32>().unwrap(), 10);
+    assert!(world.resource::<u64>().is_none());
+    let mut query = world.query::<(&u32,)>();
+    let mut merged = query.iter().map(|(n,)| *n).collect::<Vec<_>>();
+    merged.sort_unstable();
+    assert_eq!(merged, vec![10, 15]);
+    drop(query);
+    assert_eq!(world.get::<u32>(entity_map.get(entity).unwrap()), Some(&15));
+}
+
+#[test]
+fn resource_mut() {
+    let mut world = World::new();
+    world.insert_resource(10_u32);
+
+    let last_run = world.change_tick();
+    world.increment_change_tick();
+    let mut resource = world.resource_mut::<u32>().unwrap();
+    *resource += 5;
+    drop(resource);
+
+    let changed = world
+        .query::<(EcsIds, Changed<u32>)>()
+        .since(last_run)
+        .iter()
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+    assert_eq!(changed, vec![world.resource_entity().unwrap()]);
+
+    // Shared borrows of the same resource can coexist
+    let first = world.resource::<u32>().unwrap();
+    let second = world.resource::<u32>().unwrap();
+    assert_eq!((*first, *second), (15, 15));
+}
+
+#[test]
+fn query_resource() {
+    let mut world = World::new();
+    spawn!(&mut world, 10_u32);
+    spawn!(&mut world, 20_u32);
+
+    let mut query = world.query::<(&mut u32, Resource<u64>)>();
+    assert_eq!(query.iter().count(), 0);
+    drop(query);
+
+    world.insert_resource(3_u64);
+    let mut query = world.query::<(&mut u32, Resource<u64>)>();
+    for (num, &scale) in query.iter() {
+        *num *= scale as u32;
+    }
+    drop(query);
+
+    let nums = world
+        .query::<(&u32,)>()
+        .iter()
+        .map(|(&num,)| num)
+        .collect::<Vec<_>>();
+    assert_eq!(nums, vec![30, 60]);
+}
+
+#[test]
+fn query_resource_dynamic() {
+    let mut world = World::new();
+    spawn!(&mut world, 10_u32);
+    spawn!(&mut world, 20_u32);
+    world.insert_resource(3_u64);
+
+    let u32_id = world.get_or_create_type_id_ecsid::<u32>();
+    let u64_id = world.get_or_create_type_id_ecsid::<u64>();
+    let mut query = world.query_dynamic([FetchType::Immut(u32_id), FetchType::Resource(u64_id)]);
+    let sums = query
+        .iter()
+        .map(|[num, scale]| unsafe { *(num as *mut u32) as u64 + *(scale as *mut u64) })
+        .collect::<Vec<_>>();
+    assert_eq!(sums, vec![13, 23]);
+}
+
+#[test]
+fn schedule_resource() {
+    let mut world = World::new();
+    spawn!(&mut world, 10_u32);
+    world.insert_resource(3_u64);
+
+    let mut schedule = Schedule::new();
+    schedule.add_system(
+        "scale",
+        |mut query: StaticQuery<(&mut u32, Resource<u64>)>| {
+            for (num, &scale) in query.iter() {
+                *num *= scale as u32;
+            }
+        },
+    );
+    schedule.add_system("read", |_: StaticQuery<(Resource<u64>,)>| ());
+    schedule.add_system("write", |_: StaticQuery<(&mut u64,)>| ());
+
+    assert_eq!(
+        schedule.stages(&world),
+        vec![vec!["scale", "read"], vec!["write"]]
+    );
+    schedule.run(&mut world);
+    assert_eq!(*world.query::<(&u32,)>().iter().next().unwrap().0, 30);
+}
+
+#[test]
+#[should_panic(expected = "is already borrowed")]
+fn resource_conflict() {
+    let mut world = World::new();
+    world.insert_resource(10_u32);
+
+    let _query = world.query::<(&mut u32,)>();
+    world.resource::<u32>();
+}
@@ -0,0 +1,185 @@
+use crate::{Changed, ResMut, Resource, Schedule, StaticQuery, World};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn stages() {
+    let mut world = World::new();
+    spawn!(&mut world, 10_u32, 12_u64, 14_u16);
+
+    let mut schedule = Schedule::new();
+    schedule.add_system("write_u32", |_: StaticQuery<(&mut u32,)>| ());
+    schedule.add_system("read_u64", |_: StaticQuery<(&u64,)>| ());
+    schedule.add_system("read_both", |_: StaticQuery<(&u32, &u64)>| ());
+    schedule.add_system("read_u64_again", |_: StaticQuery<(&u64,)>| ());
+    schedule.add_system("changed_u16", |_: StaticQuery<(Changed<u16>,)>| ());
+    schedule.add_system("write_u16", |_: StaticQuery<(Option<&mut u16>,)>| ());
+
+    assert_eq!(
+        schedule.stages(&world),
+        vec![
+            vec!["write_u32", "read_u64", "read_u64_again", "changed_u16"],
+            vec!["read_both", "write_u16"],
+        ]
+    );
+}
+
+#[test]
+fn stages_unregistered() {
+    let world = World::new();
+
+    let mut schedule = Schedule::new();
+    schedule.add_system("write_u32", |_: StaticQuery<(&mut u32,)>| ());
+    schedule.add_system("read_u32", |_: StaticQuery<(&u32,)>| ());
+
+    assert_eq!(schedule.stages(&world), vec![vec!["write_u32", "read_u32"]]);
+}
+
+#[test]
+fn stages_ordering() {
+    let mut world = World::new();
+    spawn!(&mut world, 10_u32, 12_u64);
+
+    let mut schedule = Schedule::new();
+    let write_u32 = schedule.add_system("write_u32", |_: StaticQuery<(&mut u32,)>| ());
+    let read_u64 = schedule.add_system("read_u64", |_: StaticQuery<(&u64,)>| ());
+    let read_u32 = schedule.add_system("read_u32", |_: StaticQuery<(&u32,)>| ());
+    schedule.add_ordering(read_u64, write_u32);
+    schedule.add_ordering(read_u32, write_u32);
+
+    assert_eq!(
+        schedule.stages(&world),
+        vec![vec!["read_u64", "read_u32"], vec!["write_u32"]]
+    );
+    assert_eq!(
+        schedule.dry_run(&world),
+        "stage 0: read_u64, read_u32\nstage 1: write_u32\n"
+    );
+}
+
+#[test]
+#[should_panic(expected = "cycle")]
+fn stages_ordering_cycle() {
+    let world = World::new();
+
+    let mut schedule = Schedule::new();
+    let first = schedule.add_system("first", |_: StaticQuery<(&u32,)>| ());
+    let second = schedule.add_system("second", |_: StaticQuery<(&u64,)>| ());
+    schedule.add_ordering(first, second);
+    schedule.add_ordering(second, first);
+
+    schedule.stages(&world);
+}
+
+#[test]
+fn run() {
+    let mut world = World::new();
+    spawn!(&mut world, 10_u32, 12_u64);
+    spawn!(&mut world, 20_u32);
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let mut schedule = Schedule::new();
+    schedule.add_system("increment", |mut query: StaticQuery<(&mut u32,)>| {
+        for (num,) in query.iter() {
+            *num += 1;
+        }
+    });
+    schedule.add_system("double", |mut query: StaticQuery<(&mut u64,)>| {
+        for (num,) in query.iter() {
+            *num *= 2;
+        }
+    });
+    schedule.add_system("read", {
+        let seen = seen.clone();
+        move |mut query: StaticQuery<(&u32,)>| {
+            for (&num,) in query.iter() {
+                seen.lock().unwrap().push(num);
+            }
+        }
+    });
+
+    schedule.run(&mut world);
+    schedule.run(&mut world);
+    assert_eq!(*seen.lock().unwrap(), vec![11, 21, 12, 22]);
+    assert_eq!(
+        schedule.stages(&world),
+        vec![vec!["increment", "double"], vec!["read"]]
+    );
+    assert_eq!(*world.query::<(&u64,)>().iter().next().unwrap().0, 48);
+}
+
+#[test]
+fn run_changed() {
+    let mut world = World::new();
+    let first = spawn!(&mut world, 10_u32);
+    spawn!(&mut world, 20_u32);
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let mut schedule = Schedule::new();
+    schedule.add_system("changed", {
+        let seen = seen.clone();
+        move |mut query: StaticQuery<(&mut u32, Changed<u32>)>| {
+            for (&mut num, _) in query.iter() {
+                seen.lock().unwrap().push(num);
+            }
+        }
+    });
+
+    schedule.run(&mut world);
+    assert_eq!(*seen.lock().unwrap(), vec![10, 20]);
+
+    // The system doesn't see its own changes from the last run
+    seen.lock().unwrap().clear();
+    schedule.run(&mut world);
+    assert_eq!(*seen.lock().unwrap(), vec![]);
+
+    let mut query = world.query::<(&mut u32,)>();
+    *query.get(first).unwrap().0 = 15;
+    drop(query);
+    schedule.run(&mut world);
+    assert_eq!(*seen.lock().unwrap(), vec![15]);
+}
+
+#[test]
+fn stages_resource() {
+    let mut world = World::new();
+    spawn!(&mut world, 10_u32, 12_u64);
+    world.insert_resource(0_usize);
+
+    let mut schedule = Schedule::new();
+    schedule.add_system_with_resource("count_u32", |_: StaticQuery<(&u32,)>, _: ResMut<usize>| ());
+    schedule.add_system("read_u64", |_: StaticQuery<(&u64,)>| ());
+    schedule.add_system("read_count", |_: StaticQuery<(&u64, Resource<usize>)>| ());
+    schedule.add_system_with_resource("count_u64", |_: StaticQuery<(&u64,)>, _: ResMut<usize>| ());
+
+    assert_eq!(
+        schedule.stages(&world),
+        vec![
+            vec!["count_u32", "read_u64"],
+            vec!["read_count"],
+            vec!["count_u64"]
+        ]
+    );
+}
+
+#[test]
+fn run_resource() {
+    let mut world = World::new();
+    spawn!(&mut world, 10_u32);
+    spawn!(&mut world, 20_u32);
+
+    let mut schedule = Schedule::new();
+    schedule.add_system_with_resource(
+        "sum",
+        |mut query: StaticQuery<(&u32,)>, mut sum: ResMut<u64>| {
+            for (&num,) in query.iter() {
+                *sum += num as u64;
+            }
+        },
+    );
+
+    // Not called until the resource exists
+    schedule.run(&mut world);
+    world.insert_resource(0_u64);
+    schedule.run(&mut world);
+    assert_eq!(*world.resource::<u64>().unwrap(), 30);
+}
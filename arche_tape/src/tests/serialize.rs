@@ -0,0 +1,303 @@
+use crate::{EcsId, EntityMap, MapEntities, SerdeError, World};
+use bincode::ErrorKind;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Position(f32, f32);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Target(EcsId);
+
+impl MapEntities for Target {
+    fn map_entities(&mut self, entity_map: &EntityMap) {
+        self.0 = entity_map.get(self.0).unwrap();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Name(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Polygon { sides: u8, label: Option<char> },
+    Compound(Vec<Shape>, HashMap<String, i64>),
+}
+
+fn registered_world() -> World {
+    let mut world = World::new();
+    let registry = world.component_registry_mut();
+    registry.register::<Position>("Position");
+    registry.register::<Target>("Target");
+    registry.register::<Name>("Name");
+    registry.register::<Shape>("Shape");
+    world
+}
+
+fn round_trip(world: &mut World) -> World {
+    let mut saved = Vec::new();
+    world.save(&mut saved).unwrap();
+    let mut loaded = registered_world();
+    loaded.load(saved.as_slice()).unwrap();
+    loaded
+}
+
+#[test]
+fn save_load() {
+    let mut world = registered_world();
+    let first = world
+        .spawn()
+        .with(Position(1.0, 2.0))
+        .with(Name("first".to_string()))
+        .build();
+    let second = world
+        .spawn()
+        .with(Position(3.0, 4.0))
+        .with(Target(first))
+        .build();
+    let empty = world.spawn().build();
+
+    let mut loaded = round_trip(&mut world);
+    assert_eq!(loaded.get::<Position>(first), Some(&Position(1.0, 2.0)));
+    assert_eq!(loaded.get::<Name>(first), Some(&Name("first".to_string())));
+    assert_eq!(loaded.get::<Target>(second), Some(&Target(first)));
+    assert!(loaded.is_alive(empty));
+
+    let positions = loaded
+        .query::<(&Position,)>()
+        .iter()
+        .map(|(position,)| position.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(positions.len(), 2);
+
+    // Loaded worlds can be changed like any other
+    loaded.remove_component::<Name>(first);
+    loaded.add_component(empty, Position(5.0, 6.0));
+    assert_eq!(loaded.query::<(&Position,)>().iter().count(), 3);
+}
+
+#[test]
+fn save_load_ids() {
+    let mut world = registered_world();
+    let despawned = world.spawn().with(Position(0.0, 0.0)).build();
+    let alive = world.spawn().with(Position(1.0, 1.0)).build();
+    world.despawn(despawned);
+    let reused = world.spawn().build();
+    world.despawn(reused);
+
+    let mut loaded = round_trip(&mut world);
+    assert!(!loaded.is_alive(despawned) && !loaded.is_alive(reused));
+    assert!(loaded.is_alive(alive));
+    assert_eq!(loaded.despawned(), &[despawned, reused]);
+
+    // Both worlds reuse the same index with the same generation
+    let next = world.spawn().build();
+    assert_eq!(loaded.spawn().build(), next);
+}
+
+#[test]
+fn save_load_entity_components() {
+    let mut world = registered_world();
+    let likes = world.spawn().build();
+    let apples = world.spawn().build();
+    let entity = world.spawn().with(Position(1.0, 2.0)).build();
+    world.add_component_dynamic(entity, likes);
+    world.add_component_dynamic(entity, EcsId::pair(likes, apples));
+    world.add_pair(entity, Position(3.0, 4.0), apples);
+
+    let mut loaded = round_trip(&mut world);
+    assert!(loaded.get_component_dynamic(entity, likes).is_some());
+    assert_eq!(loaded.targets_dynamic(entity, likes), vec![apples]);
+    assert_eq!(loaded.targets::<Position>(entity), vec![apples]);
+    let pair = loaded.pair_id::<Position>(apples);
+    let data = loaded.get_component_dynamic(entity, pair).unwrap();
+    assert_eq!(unsafe { &*(data as *const Position) }, &Position(3.0, 4.0));
+}
+
+#[test]
+fn save_load_ticks() {
+    let mut world = registered_world();
+    let entity = world.spawn().with(Position(1.0, 2.0)).build();
+    world.increment_change_tick();
+    let last_run = world.change_tick();
+    world.increment_change_tick();
+    world.get_mut::<Position>(entity).unwrap().0 = 10.0;
+
+    let loaded = round_trip(&mut world);
+    assert_eq!(loaded.change_tick(), world.change_tick());
+    let changed = loaded
+        .query::<(crate::EcsIds, crate::Changed<Position>)>()
+        .since(last_run)
+        .iter()
+        .count();
+    assert_eq!(changed, 1);
+}
+
+#[test]
+fn save_unregistered() {
+    let mut world = registered_world();
+    world.spawn().with(10_u32).build();
+    let u32_id = world.get_or_create_type_id_ecsid::<u32>();
+
+    match world.save(Vec::new()) {
+        Err(SerdeError::Unregistered(id)) => assert_eq!(id, u32_id),
+        _ => panic!("Saving an unregistered component should fail"),
+    }
+}
+
+#[test]
+fn load_unknown_name() {
+    let mut world = registered_world();
+    world.spawn().with(Position(1.0, 2.0)).build();
+    let mut saved = Vec::new();
+    world.save(&mut saved).unwrap();
+
+    let mut loaded = World::new();
+    let other = loaded.spawn().build();
+    match loaded.load(saved.as_slice()) {
+        Err(SerdeError::UnknownName(name)) => assert_eq!(name, "Position"),
+        _ => panic!("Loading an unknown component should fail"),
+    }
+    // The world is untouched by a failed load
+    assert!(loaded.is_alive(other));
+    assert_eq!(loaded.get::<Position>(other), None);
+}
+
+#[test]
+fn save_load_shapes() {
+    let mut world = registered_world();
+    let shapes = vec![
+        Shape::Empty,
+        Shape::Circle(-1.5),
+        Shape::Polygon {
+            sides: 3,
+            label: Some('△'),
+        },
+        Shape::Compound(
+            vec![Shape::Circle(2.0), Shape::Empty],
+            std::iter::once(("weight".to_string(), -7)).collect(),
+        ),
+    ];
+    let entities = shapes
+        .into_iter()
+        .map(|shape| world.spawn().with(shape).build())
+        .collect::<Vec<_>>();
+
+    let mut loaded = round_trip(&mut world);
+    for entity in entities {
+        assert_eq!(loaded.get::<Shape>(entity), world.get::<Shape>(entity));
+    }
+}
+
+#[test]
+fn load_truncated() {
+    let mut world = registered_world();
+    world.spawn().with(Name("name".to_string())).build();
+    let mut saved = Vec::new();
+    world.save(&mut saved).unwrap();
+    saved.pop();
+
+    match registered_world().load(saved.as_slice()) {
+        Err(SerdeError::Format(err)) if matches!(*err, ErrorKind::Io(_)) => (),
+        _ => panic!("Loading a truncated world should fail"),
+    }
+}
+
+#[test]
+fn load_huge_length() {
+    // A change tick followed by a generations length of u64::MAX and nothing else
+    let mut saved = vec![0, 253];
+    saved.extend_from_slice(&u64::MAX.to_le_bytes());
+
+    match registered_world().load(saved.as_slice()) {
+        Err(SerdeError::Format(err)) if matches!(*err, ErrorKind::Io(_)) => (),
+        _ => panic!("Loading a world with a length past its end should fail"),
+    }
+}
+
+#[test]
+fn load_invalid_free_index() {
+    let mut world = registered_world();
+    let alive = world.spawn().with(Position(1.0, 2.0)).build();
+    let dead = world.spawn().build();
+    world.despawn(dead);
+
+    for free_indices in [vec![alive.uindex()], vec![dead.uindex(); 2], vec![100]] {
+        world.entities.despawned = free_indices;
+        let mut saved = Vec::new();
+        world.save(&mut saved).unwrap();
+        match registered_world().load(saved.as_slice()) {
+            Err(SerdeError::Format(err)) if matches!(*err, ErrorKind::Custom(_)) => (),
+            _ => panic!("Loading a free index that isn't a dead entity should fail"),
+        }
+    }
+}
+
+#[test]
+fn load_out_of_range_index() {
+    let out_of_range = EcsId::new(100, 0);
+    let corruptions: [fn(&mut World, EcsId); 3] = [
+        |world, id| world.resource_entity = Some(id),
+        |world, id| {
+            world
+                .type_id_to_ecs_id
+                .insert(std::any::TypeId::of::<Shape>(), id);
+        },
+        |world, id| {
+            let archetype = world.archetypes.iter_mut().find(|a| !a.entities.is_empty());
+            archetype.unwrap().entities[0] = id;
+        },
+    ];
+
+    for corrupt in corruptions {
+        let mut world = registered_world();
+        world.spawn().with(Position(1.0, 2.0)).build();
+        corrupt(&mut world, out_of_range);
+        let mut saved = Vec::new();
+        world.save(&mut saved).unwrap();
+        match registered_world().load(saved.as_slice()) {
+            Err(SerdeError::Format(err)) if matches!(*err, ErrorKind::Custom(_)) => (),
+            _ => panic!("Loading an entity index past the saved generations should fail"),
+        }
+    }
+}
+
+#[test]
+fn save_deterministic() {
+    let saves = (0..2)
+        .map(|_| {
+            let mut world = registered_world();
+            let entity = world.spawn().with(Position(1.0, 2.0)).build();
+            world.spawn().with(Target(entity)).build();
+            world.spawn().with(Name("name".to_string())).build();
+            world.spawn().with(Shape::Empty).build();
+            let mut saved = Vec::new();
+            world.save(&mut saved).unwrap();
+            saved
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(saves[0], saves[1]);
+}
+
+#[test]
+fn load_keeps_hooks() {
+    let mut world = registered_world();
+    let entity = world.spawn().with(Name("bob".to_string())).build();
+    world.spawn().with(Target(entity)).build();
+    let mut saved = Vec::new();
+    world.save(&mut saved).unwrap();
+
+    world.register_clone::<Name>();
+    world.register_debug::<Name>();
+    world.register_map_entities::<Target>();
+    world.load(saved.as_slice()).unwrap();
+
+    let clone = world.clone_entity(entity);
+    assert_eq!(world.get::<Name>(clone), Some(&Name("bob".to_string())));
+    assert!(world.debug_string().contains("Name(\"bob\")"));
+    let target_id = world.get_or_create_type_id_ecsid::<Target>();
+    let meta = world.get_component_meta(target_id).unwrap();
+    assert!(meta.map_entities_fn.is_some());
+}
@@ -1,4 +1,4 @@
-use crate::{spawn, world::ComponentMeta, EcsId, World};
+use crate::{spawn, world::ComponentMeta, EcsError, EcsId, EcsIds, World};
 
 #[test]
 pub fn get() {
@@ -16,6 +16,80 @@ pub fn get() {
     assert!(*str_comp == "AWDAWDAWD");
 }
 
+#[test]
+pub fn get_typed() {
+    let mut world = World::new();
+
+    let entity = spawn!(&mut world, 10_u32, 12_u64);
+    let entity2 = spawn!(&mut world, 14_u32);
+
+    assert_eq!(world.get::<u32>(entity), Some(&10));
+    assert_eq!(world.get::<u64>(entity2), None);
+    assert_eq!(world.get::<u128>(entity), None);
+
+    *world.get_mut::<u32>(entity2).unwrap() += 1;
+    assert_eq!(world.get::<u32>(entity2), Some(&15));
+
+    world.despawn(entity2);
+    assert_eq!(world.get::<u32>(entity2), None);
+}
+
+#[test]
+pub fn get_many_mut() {
+    let mut world = World::new();
+
+    let entity = spawn!(&mut world, 10_u32, 12_u64);
+    let entity2 = spawn!(&mut world, 14_u32);
+
+    let (left, right) = world.get_many_mut::<(u32, u64)>(entity).unwrap();
+    *left = *right as u32;
+    *right += 1;
+    assert_eq!(world.get::<u32>(entity), Some(&12));
+    assert_eq!(world.get::<u64>(entity), Some(&13));
+
+    assert!(world.get_many_mut::<(u32, u64)>(entity2).is_none());
+}
+
+#[test]
+pub fn get_many_mut_missing_not_changed() {
+    let mut world = World::new();
+    let entity = spawn!(&mut world, 10_u32);
+    world.get_or_create_type_id_ecsid::<u64>();
+    world.increment_change_tick();
+    let last_run = world.change_tick();
+    world.increment_change_tick();
+
+    assert!(world.get_many_mut::<(u32, u64)>(entity).is_none());
+    let changed = world
+        .query::<(EcsIds, crate::Changed<u32>)>()
+        .since(last_run)
+        .iter()
+        .count();
+    assert_eq!(changed, 0);
+}
+
+#[test]
+#[should_panic(expected = "Attempted to get the same component mutably more than once")]
+pub fn get_many_mut_aliasing() {
+    let mut world = World::new();
+
+    let entity = spawn!(&mut world, 10_u32, 12_u64);
+    let _ = world.get_many_mut::<(u32, u64, u32)>(entity);
+}
+
+#[test]
+pub fn get_component_dynamic() {
+    let mut world = World::new();
+
+    let entity = spawn!(&mut world, 10_u32);
+    let u32_id = world.get_or_create_type_id_ecsid::<u32>();
+    let u64_id = world.get_or_create_type_id_ecsid::<u64>();
+
+    let ptr = world.get_component_dynamic(entity, u32_id).unwrap();
+    assert_eq!(unsafe { *(ptr as *const u32) }, 10);
+    assert!(world.get_component_dynamic(entity, u64_id).is_none());
+}
+
 #[test]
 pub fn entity_archetype_lookup() {
     let mut world = World::new();
@@ -213,8 +287,6 @@ pub fn despawn_meta_update() {
 
 #[test]
 pub fn despawn_component_entity() {
-    // TODO: Removing entities when they despawn not yet implemented
-    return;
     let mut world = World::new();
 
     unsafe {
@@ -255,6 +327,62 @@ pub fn despawn_component_entity() {
     }
 }
 
+#[test]
+pub fn despawn_component_entity_keeps_other_components() {
+    let mut world = World::new();
+
+    let tag = world.spawn().build();
+    let e1 = world
+        .spawn()
+        .with(10_u32)
+        .with_dynamic(tag)
+        .with(1_u64)
+        .build();
+    let e2 = world.spawn().with(12_u32).with_dynamic(tag).build();
+    let e3 = world.spawn().with(14_u32).build();
+
+    assert!(world.despawn(tag));
+    assert!(world.is_alive(tag) == false);
+    assert!(!world.lock_lookup.contains_key(&tag));
+    for archetype in world.archetypes.iter() {
+        assert!(!archetype.comp_lookup.contains_key(&tag));
+    }
+
+    let mut checks = vec![(e3, 14), (e2, 12), (e1, 10)].into_iter();
+    for (entity, data) in world.query::<(EcsIds, &u32)>().iter() {
+        assert_eq!(checks.next().unwrap(), (entity, *data));
+    }
+    assert!(checks.next().is_none());
+
+    assert!(world.has_component::<u64>(e1));
+    assert!(world.has_component::<u64>(e2) == false);
+
+    // The despawned tag's index gets reused by this spawn and must not be treated as the old tag
+    let new_entity = world.spawn().build();
+    assert!(new_entity.uindex() == tag.uindex());
+    let mut count = 0;
+    world.query::<(&u32,)>().iter().for_each(|_| count += 1);
+    assert!(count == 3);
+}
+
+#[test]
+pub fn despawn_type_component_entity() {
+    let mut world = World::new();
+
+    let e1 = spawn!(&mut world, 10_u32, 12_u64);
+    let u64_id = world.get_or_create_type_id_ecsid::<u64>();
+
+    assert!(world.despawn(u64_id));
+    assert!(world.has_component::<u64>(e1) == false);
+    assert!(world.has_component::<u32>(e1));
+
+    // A new id gets created for the type after its old one was despawned
+    world.add_component(e1, 14_u64);
+    assert!(world.get_or_create_type_id_ecsid::<u64>() != u64_id);
+    let mut q = world.query::<(&u32, &u64)>();
+    assert_eq!(q.get(e1), Some((&10, &14)));
+}
+
 // TODO: Boxy can you make the following tests actually work?
 // Currently they basically just want to not panic, but they should check capacity if possible
 #[test]
@@ -277,3 +405,416 @@ pub fn spawn_with_capacity_zero() -> () {
     let entity = world.spawn_with_capacity(0).build();
     assert_eq!(entity, EcsId::new(0, 0));
 }
+
+#[test]
+pub fn reserve_entities() {
+    let mut world = World::new();
+
+    let e1 = spawn!(&mut world, 10_u32);
+    let e2 = spawn!(&mut world, 12_u32);
+    world.despawn(e1);
+
+    let reserved = world.reserve_entity();
+    let reserved_2 = world.reserve_entities(2).collect::<Vec<_>>();
+    // The despawned index gets reused first
+    assert!(reserved.uindex() == e1.uindex());
+    assert!(reserved != e1);
+    assert!(reserved_2[0] != reserved_2[1]);
+
+    assert!(world.is_alive(reserved));
+    assert!(world.is_alive(reserved_2[0]));
+    assert!(world.is_alive(reserved_2[1]));
+    assert!(world.is_alive(e1) == false);
+    assert!(world.get_entity_meta(reserved).is_none());
+
+    // Mutable access flushes the reserved entities into the empty archetype
+    world.add_component(reserved, 14_u32);
+    assert!(world.get_entity_meta(reserved_2[0]).is_some());
+    let meta = world.get_entity_meta(reserved_2[1]).unwrap();
+    assert!(
+        world.archetypes[meta.instance_meta.archetype.0]
+            .comp_ids
+            .is_empty()
+    );
+
+    let mut checks = vec![12, 14].into_iter();
+    for (data,) in world.query::<(&u32,)>().iter() {
+        assert_eq!(*data, checks.next().unwrap());
+    }
+    assert!(checks.next().is_none());
+
+    let e3 = world.spawn().build();
+    assert!(e3 != reserved && e3 != reserved_2[0] && e3 != reserved_2[1] && e3 != e2);
+}
+
+#[test]
+pub fn removed_and_despawned_logs() {
+    struct Unused;
+    let mut world = World::new();
+
+    let e1 = spawn!(&mut world, 10_u32, 12_u64);
+    let e2 = spawn!(&mut world, 14_u32);
+    let e3 = spawn!(&mut world, 16_u32);
+    assert!(world.removed::<u32>().is_empty());
+    assert!(world.removed::<Unused>().is_empty());
+
+    world.remove_component::<u64>(e1);
+    world.despawn(e2);
+    world.remove_component::<u32>(e3);
+
+    assert!(world.removed::<u64>() == [e1]);
+    assert!(world.removed::<u32>() == [e2, e3]);
+    assert!(world.despawned() == [e2]);
+
+    world.clear_removed();
+    assert!(world.removed::<u32>().is_empty());
+    assert!(world.removed::<u64>().is_empty());
+    assert!(world.despawned() == [e2]);
+
+    world.despawn(e1);
+    assert!(world.removed::<u32>() == [e1]);
+    assert!(world.despawned() == [e2, e1]);
+    world.clear_despawned();
+    assert!(world.despawned().is_empty());
+}
+
+#[test]
+pub fn try_add_remove() {
+    use std::rc::Rc;
+
+    let mut world = World::new();
+    let rc = Rc::new(());
+
+    let e1 = spawn!(&mut world, 10_u32);
+    let dead = spawn!(&mut world);
+    world.despawn(dead);
+
+    assert!(world.try_add(e1, 12_u64) == Ok(()));
+    assert!(world.try_add(e1, 14_u64) == Err(EcsError::AlreadyPresent));
+    assert!(world.try_add(dead, rc.clone()) == Err(EcsError::NoSuchEntity));
+    assert!(Rc::strong_count(&rc) == 1);
+    assert!(*world.get::<u64>(e1).unwrap() == 12);
+
+    assert!(world.try_remove::<u64>(e1) == Ok(()));
+    assert!(world.try_remove::<u64>(e1) == Err(EcsError::Missing));
+    assert!(world.try_remove::<u128>(e1) == Err(EcsError::Missing));
+    assert!(world.try_remove::<u32>(dead) == Err(EcsError::NoSuchEntity));
+
+    let u32_id = world.get_or_create_type_id_ecsid::<u32>();
+    let tag = world.spawn().build();
+    assert!(world.try_add_component_dynamic(e1, u32_id) == Err(EcsError::NotUnitComponent));
+    assert!(world.try_add_component_dynamic(e1, tag) == Ok(()));
+    assert!(world.try_add_component_dynamic(e1, tag) == Err(EcsError::AlreadyPresent));
+    assert!(world.try_remove_component_dynamic(e1, tag) == Ok(()));
+    assert!(world.try_remove_component_dynamic(e1, tag) == Err(EcsError::Missing));
+}
+
+#[test]
+pub fn insert_overwrites() {
+    use std::rc::Rc;
+
+    let mut world = World::new();
+    let rc = Rc::new(());
+
+    let e1 = spawn!(&mut world, 10_u32, rc.clone());
+    let e2 = spawn!(&mut world, 12_u32);
+    let last_run = world.increment_change_tick();
+    world.increment_change_tick();
+
+    assert!(world.insert(e1, 14_u32) == Ok(()));
+    assert!(world.insert(e1, Rc::new(())) == Ok(()));
+    assert!(Rc::strong_count(&rc) == 1);
+    assert!(world.insert(e2, 1_u64) == Ok(()));
+
+    let archetype = world.get_entity_meta(e1).unwrap().instance_meta.archetype.0;
+    assert!(world.archetypes[archetype].comp_ids.len() == 2);
+    assert!(*world.get::<u32>(e1).unwrap() == 14);
+    assert!(*world.get::<u64>(e2).unwrap() == 1);
+
+    let mut q = world.query::<(&u32, crate::Changed<u32>)>().since(last_run);
+    assert!(q.get(e1) == Some((&14, ())));
+    assert!(q.get(e2).is_none());
+}
+
+#[test]
+pub fn try_build_not_unit() {
+    use std::rc::Rc;
+
+    let mut world = World::new();
+    let rc = Rc::new(());
+
+    let u32_id = world.get_or_create_type_id_ecsid::<u32>();
+    let mut builder = world
+        .spawn()
+        .with(rc.clone())
+        .with_dynamic(u32_id)
+        .with(10_u64);
+    assert!(builder.try_build() == Err(EcsError::NotUnitComponent));
+    drop(builder);
+
+    assert!(Rc::strong_count(&rc) == 1);
+    assert!(world.query::<(&u64,)>().iter().count() == 0);
+    assert!(world.query::<(&Rc<()>,)>().iter().count() == 0);
+}
+
+#[test]
+pub fn take() {
+    let mut world = World::new();
+
+    let e1 = spawn!(&mut world, 10_u32, String::from("hello"));
+    let e2 = spawn!(&mut world, 12_u32);
+
+    let string = world.take::<String>(e1).unwrap();
+    assert!(string == "hello");
+    assert!(world.take::<String>(e1).is_none());
+    assert!(world.take::<u64>(e1).is_none());
+    assert!(world.removed::<String>() == [e1]);
+
+    world.add_component(e2, string);
+    assert!(world.get::<String>(e2).unwrap() == "hello");
+    assert!(*world.get::<u32>(e1).unwrap() == 10);
+
+    let u32_id = world.get_or_create_type_id_ecsid::<u32>();
+    let mut buffer = [0_u8; 4];
+    unsafe {
+        assert!(world.take_dynamic(e2, u32_id, buffer.as_mut_ptr()) == Ok(()));
+        assert!(world.take_dynamic(e2, u32_id, buffer.as_mut_ptr()) == Err(EcsError::Missing));
+    }
+    assert!(u32::from_ne_bytes(buffer) == 12);
+    assert!(world.get::<String>(e2).unwrap() == "hello");
+}
+
+#[test]
+pub fn bundles() {
+    use std::rc::Rc;
+
+    let mut world = World::new();
+
+    let e1 = world.spawn_bundle((10_u32, 12_u64));
+    let archetypes = world.archetypes.len();
+    world.add_bundle(e1, (String::from("hello"), 14_u16, Rc::new(())));
+    // Moves straight to the final archetype without creating any intermediate ones
+    assert!(world.archetypes.len() == archetypes + 1);
+    assert!(world.get::<String>(e1).unwrap() == "hello");
+    assert!(*world.get::<u16>(e1).unwrap() == 14);
+    assert!(*world.get::<u32>(e1).unwrap() == 10);
+
+    let rc = Rc::new(());
+    assert!(world.try_add_bundle(e1, (rc.clone(), 1_u8)) == Err(EcsError::AlreadyPresent));
+    assert!(Rc::strong_count(&rc) == 1);
+    assert!(world.has_component::<u8>(e1) == false);
+
+    world.remove_bundle::<(u32, String, Rc<()>)>(e1);
+    assert!(world.removed::<String>() == [e1]);
+    assert!(world.try_remove_bundle::<(u64, u32)>(e1) == Err(EcsError::Missing));
+    assert!(world.try_remove_bundle::<(u64, i128)>(e1) == Err(EcsError::Missing));
+    assert!(*world.get::<u64>(e1).unwrap() == 12);
+    assert!(*world.get::<u16>(e1).unwrap() == 14);
+
+    let e2 = world.spawn_bundle((12_u64, 14_u16));
+    let mut query = world.query::<(EcsIds, &u64, &u16)>();
+    let ids = query.iter().map(|(id, _, _)| id).collect::<Vec<_>>();
+    assert!(ids == [e1, e2]);
+}
+
+#[test]
+#[should_panic(expected = "Attempted to add or remove the same component twice in a bundle")]
+pub fn bundle_duplicate() {
+    let mut world = World::new();
+    let entity = world.spawn().build();
+    world.add_bundle(entity, (10_u32, 12_u32));
+}
+
+#[test]
+pub fn spawn_batch() {
+    struct Zero;
+
+    let mut world = World::new();
+
+    let first = world.spawn_bundle((0_u32, String::from("0"), Zero));
+    let archetypes = world.archetypes.len();
+    let spawned = world
+        .spawn_batch((1..100_u32).map(|n| (n, n.to_string(), Zero)))
+        .to_vec();
+    assert!(world.archetypes.len() == archetypes);
+    assert!(spawned.len() == 99);
+
+    for (n, &entity) in std::iter::once(&first).chain(spawned.iter()).enumerate() {
+        assert!(*world.get::<u32>(entity).unwrap() == n as u32);
+        assert!(*world.get::<String>(entity).unwrap() == n.to_string());
+        assert!(world.has_component::<Zero>(entity));
+    }
+
+    let mut query = world.query::<(EcsIds, &u32, &String)>();
+    assert!(query.iter().count() == 100);
+    drop(query);
+
+    // Registering u64 spawns an entity so do it before despawning to make sure the batch reuses the despawned id
+    world.get_or_create_type_id_ecsid::<u64>();
+    world.despawn(spawned[0]);
+    let respawned = world.spawn_batch(vec![(1_u64,), (2_u64,)]).to_vec();
+    assert!(respawned[0] == EcsId::new(spawned[0].uindex() as u32, 1));
+    assert!(*world.get::<u64>(respawned[1]).unwrap() == 2);
+}
+
+#[test]
+pub fn archetype_lookup() {
+    let mut world = World::new();
+
+    let e1 = spawn!(&mut world, 10_u32, 12_u64);
+    let e2 = spawn!(&mut world, 12_u64, 10_u32);
+    let archetype = world.get_entity_meta(e1).unwrap().instance_meta.archetype.0;
+    assert!(world.get_entity_meta(e2).unwrap().instance_meta.archetype.0 == archetype);
+
+    let e3 = spawn!(&mut world, 10_u32);
+    world.add_component(e3, 12_u64);
+    assert!(world.get_entity_meta(e3).unwrap().instance_meta.archetype.0 == archetype);
+
+    let u32_id = world.get_or_create_type_id_ecsid::<u32>();
+    let u64_id = world.get_or_create_type_id_ecsid::<u64>();
+    let mut comp_ids = vec![u32_id, u64_id];
+    comp_ids.sort();
+    assert!(world.archetype_lookup[&comp_ids].0 == archetype);
+    assert!(world.archetype_lookup.len() == world.archetypes.len());
+
+    let tag = spawn!(&mut world);
+    world.add_component_dynamic(e1, tag);
+    let tagged_archetype = world.get_entity_meta(e1).unwrap().instance_meta.archetype.0;
+    world.despawn(tag);
+    // The emptied out archetype must never be found again
+    assert!(world.archetype_lookup.len() == world.archetypes.len() - 1);
+    assert!(
+        world
+            .archetype_lookup
+            .values()
+            .all(|idx| idx.0 != tagged_archetype)
+    );
+    assert!(world.get_entity_meta(e1).unwrap().instance_meta.archetype.0 == archetype);
+}
+
+#[test]
+pub fn archetype_edges() {
+    fn archetype_of(world: &World, entity: EcsId) -> usize {
+        world
+            .get_entity_meta(entity)
+            .unwrap()
+            .instance_meta
+            .archetype
+            .0
+    }
+    fn add_edge(world: &mut World, archetype: usize, comp_id: EcsId) -> Option<usize> {
+        world.archetypes[archetype].add_edges.lookup_id(comp_id)
+    }
+    fn remove_edge(world: &mut World, archetype: usize, comp_id: EcsId) -> Option<usize> {
+        world.archetypes[archetype].remove_edges.lookup_id(comp_id)
+    }
+
+    let mut world = World::new();
+    let u32_id = world.get_or_create_type_id_ecsid::<u32>();
+    let u64_id = world.get_or_create_type_id_ecsid::<u64>();
+
+    let entity = spawn!(&mut world, 10_u32);
+    let u32_arch = archetype_of(&world, entity);
+    world.add_component(entity, 12_u64);
+    let both_arch = archetype_of(&world, entity);
+
+    // Both directions are linked as soon as the archetype is created
+    assert!(add_edge(&mut world, u32_arch, u64_id) == Some(both_arch));
+    assert!(remove_edge(&mut world, both_arch, u64_id) == Some(u32_arch));
+    // Add and remove edges don't share a table
+    assert!(remove_edge(&mut world, u32_arch, u64_id).is_none());
+    assert!(add_edge(&mut world, both_arch, u64_id).is_none());
+
+    world.remove_component::<u32>(entity);
+    let u64_arch = archetype_of(&world, entity);
+    assert!(remove_edge(&mut world, both_arch, u32_id) == Some(u64_arch));
+    assert!(add_edge(&mut world, u64_arch, u32_id) == Some(both_arch));
+
+    world.add_component(entity, 14_u32);
+    assert!(archetype_of(&world, entity) == both_arch);
+    assert!(*world.get::<u32>(entity).unwrap() == 14);
+}
+
+#[test]
+pub fn compact() {
+    struct Buff<const N: usize>;
+
+    let mut world = World::new();
+
+    let e1 = spawn!(&mut world, 10_u32);
+    let e2 = spawn!(&mut world, 12_u32, String::from("hello"));
+    world.add_component(e1, Buff::<0>);
+    world.add_component(e1, Buff::<1>);
+    world.remove_component::<Buff<0>>(e1);
+    world.remove_component::<Buff<1>>(e1);
+    let tag = spawn!(&mut world);
+    world.add_component_dynamic(e2, tag);
+    world.despawn(tag);
+
+    let occupied = world
+        .archetypes
+        .iter()
+        .filter(|archetype| !archetype.entities.is_empty())
+        .count();
+    assert!(world.archetypes.len() > occupied + 1);
+
+    world.compact();
+    // Only the archetype with no components is kept while empty
+    assert!(world.archetypes.len() == occupied);
+    assert!(world.archetype_lookup.len() == world.archetypes.len());
+    assert!(*world.get::<u32>(e1).unwrap() == 10);
+    assert!(world.get::<String>(e2).unwrap() == "hello");
+
+    let mut query = world.query::<(EcsIds, &u32)>();
+    assert!(query.iter().map(|(id, _)| id).collect::<Vec<_>>() == [e1, e2]);
+    drop(query);
+
+    world.add_component(e1, Buff::<0>);
+    world.add_component(e1, String::from("world"));
+    world.remove_component::<Buff<0>>(e1);
+    let mut query = world.query::<(EcsIds, &String)>();
+    assert!(query.iter().map(|(id, _)| id).collect::<Vec<_>>() == [e2, e1]);
+    drop(query);
+
+    world.despawn(e1);
+    world.despawn(e2);
+    world.compact();
+    assert!(world.archetypes.len() == 1);
+    assert!(world.archetypes[0].comp_ids.is_empty());
+    let e3 = spawn!(&mut world, 14_u32);
+    assert!(*world.get::<u32>(e3).unwrap() == 14);
+}
+
+#[test]
+fn debug_string() {
+    let mut world = World::new();
+    world.register_debug::<u32>();
+    let likes = world.spawn().build();
+    let entity = spawn!(&mut world, 10_u32, 12_u64);
+    world.add_component_dynamic(entity, likes);
+    world.add_component_dynamic(entity, EcsId::pair(likes, likes));
+
+    let u32_id = world.get_or_create_type_id_ecsid::<u32>();
+    let debug = world.debug_string();
+    assert!(debug.contains(&format!("  component {}: u32\n", u32_id)));
+    assert!(debug.contains(&format!("  component {}: {}\n", likes, likes)));
+    assert!(debug.contains(&format!(
+        "  entity {}: u32 = 10, {}, u64 = .., ({}, {})\n",
+        entity, likes, likes, likes
+    )));
+    assert!(debug.contains("archetype 3: 1 rows\n"));
+
+    let _query = world.query::<(&mut u32,)>();
+    assert!(world.debug_string().contains("u32 = <borrowed>"));
+}
+
+#[test]
+fn component_meta_type_name() {
+    assert_eq!(ComponentMeta::from_generic::<u32>().type_name, Some("u32"));
+    assert_eq!(ComponentMeta::unit().type_name, None);
+    assert!(
+        ComponentMeta::from_generic_debug::<u32>()
+            .debug_fn
+            .is_some()
+    );
+}
@@ -2,15 +2,20 @@ use super::entities::{EcsId, Entities};
 use crate::{
     array_vec::ArrayVec,
     bitset_iterator::{BitsetIterator, Bitsetsss, Bitvec},
+    bundle::Bundle,
+    command_buffer::CommandBuffer,
     dyn_query::{DynQuery, FetchType},
+    resource::{Res, ResMut},
     static_query::StaticQuery,
-    Component,
+    utils::EitherGuard,
+    BorrowError, Component, EcsError,
 };
 use std::cell::UnsafeCell;
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{RwLock, TryLockError};
 use std::{any::TypeId, slice::Iter};
-use untyped_vec::UntypedVec;
+use untyped_vec::{TypeInfo, UntypedVec};
 
 pub struct ArchetypeIter<'a, const N: usize> {
     archetypes: &'a [Archetype],
@@ -26,12 +31,14 @@ impl<'a, const N: usize> Iterator for ArchetypeIter<'a, N> {
 }
 
 const CACHE_SIZE: usize = 4;
-pub struct AddRemoveCache {
+/// Edges of the archetype graph in one direction, maps a component id to the archetype that is reached by adding or
+/// removing that component. The most recently used edges are kept in ``cache`` to avoid hashing
+pub struct ArchetypeEdges {
     cache: ArrayVec<(EcsId, usize), CACHE_SIZE>,
     lookup: HashMap<EcsId, usize, crate::utils::TypeIdHasherBuilder>,
 }
 
-impl AddRemoveCache {
+impl ArchetypeEdges {
     pub(crate) fn new() -> Self {
         Self {
             cache: ArrayVec::new(),
@@ -58,7 +65,162 @@ impl AddRemoveCache {
         self.cache.push_start((component_id, archetype));
         self.lookup.insert(component_id, archetype);
     }
+
+    /// Drops the edge for ``component_id``, used when the id of a dead pair can be reused by a new pair
+    fn remove_id(&mut self, component_id: EcsId) {
+        self.cache = ArrayVec::new();
+        self.lookup.remove(&component_id);
+    }
+
+    /// Updates the archetype indices after ``World::compact``, edges to removed archetypes are dropped
+    fn remap(&mut self, remap: &[Option<usize>]) {
+        self.cache = ArrayVec::new();
+        self.lookup.retain(|_, idx| match remap[*idx] {
+            Some(new_idx) => {
+                *idx = new_idx;
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+/// The ticks at which a component was added to an entity and last mutably accessed, see ``World::change_tick``
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComponentTicks {
+    pub added: u32,
+    pub changed: u32,
+}
+
+impl ComponentTicks {
+    pub fn new(change_tick: u32) -> Self {
+        Self {
+            added: change_tick,
+            changed: change_tick,
+        }
+    }
+
+    /// Returns true if the component was added after ``last_run``
+    pub fn is_added(&self, last_run: u32) -> bool {
+        self.added > last_run
+    }
+
+    /// Returns true if the component was added or mutably accessed after ``last_run``
+    pub fn is_changed(&self, last_run: u32) -> bool {
+        self.changed > last_run
+    }
+}
+
+/// A component storage of an archetype, ``added`` and ``changed`` have one tick per element of ``data`` and are kept
+/// in the same order. They are separate vecs rather than one of ``ComponentTicks`` so that queries can mark a slice of
+/// rows as changed with a single contiguous fill
+pub struct Column {
+    pub(crate) data: UntypedVec,
+    pub(crate) added: Vec<u32>,
+    pub(crate) changed: Vec<u32>,
+}
+
+impl Column {
+    /// # Safety
+    ///
+    ///    ``type_info`` must be valid
+    pub unsafe fn new(type_info: TypeInfo) -> Self {
+        Self {
+            data: unsafe { UntypedVec::new_from_raw(type_info) },
+            added: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
+
+    /// Creates an empty column with the same type as ``from``
+    pub fn new_from_column(from: &mut Column) -> Self {
+        Self {
+            data: UntypedVec::new_from_untyped_vec(&mut from.data),
+            added: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.added.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+    }
+
+    /// The ticks of the element at ``row``
+    pub fn ticks(&self, row: usize) -> ComponentTicks {
+        ComponentTicks {
+            added: self.added[row],
+            changed: self.changed[row],
+        }
+    }
+
+    /// # Safety
+    ///
+    ///    ``src`` must point to a valid instance of the column's type which must not be used again
+    pub unsafe fn push_raw(&mut self, src: *mut core::mem::MaybeUninit<u8>, ticks: ComponentTicks) {
+        unsafe { self.data.push_raw(src) };
+        self.added.push(ticks.added);
+        self.changed.push(ticks.changed);
+    }
+
+    /// # Safety
+    ///
+    ///    The other Column must be of the same type
+    pub unsafe fn swap_move_element_to_other_vec(&mut self, other: &mut Column, element: usize) {
+        unsafe {
+            self.data
+                .swap_move_element_to_other_vec(&mut other.data, element)
+        };
+        other.added.push(self.added.swap_remove(element));
+        other.changed.push(self.changed.swap_remove(element));
+    }
+
+    /// # Safety
+    ///
+    ///    The other Column must be of the same type
+    pub unsafe fn move_all_to_other_vec(&mut self, other: &mut Column) {
+        unsafe { self.data.move_all_to_other_vec(&mut other.data) };
+        other.added.append(&mut self.added);
+        other.changed.append(&mut self.changed);
+    }
+
+    /// Reserves capacity for at least ``additional`` more elements
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+        self.added.reserve(additional);
+        self.changed.reserve(additional);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+        self.added.shrink_to_fit();
+        self.changed.shrink_to_fit();
+    }
+
+    pub fn swap_remove(&mut self, element: usize) {
+        self.data.swap_remove(element);
+        self.added.swap_remove(element);
+        self.changed.swap_remove(element);
+    }
+
+    /// # Safety
+    ///
+    ///    ``dst`` must be valid for writes of the column type's size, it does not need to be aligned
+    pub unsafe fn swap_remove_into(
+        &mut self,
+        element: usize,
+        dst: *mut core::mem::MaybeUninit<u8>,
+    ) {
+        unsafe { self.data.swap_remove_into(element, dst) };
+        self.added.swap_remove(element);
+        self.changed.swap_remove(element);
+    }
 }
+
 pub struct Archetype {
     /// A lookup of a component's TypeId to the index into component_storages/type_ids
     pub(crate) comp_lookup: HashMap<EcsId, usize, crate::utils::TypeIdHasherBuilder>,
@@ -69,13 +231,16 @@ pub struct Archetype {
     /// Component storages are sorted such that lower type_ids are first, this means that when adding/removing components we dont need to
     /// go through the lookup hashmap on the other archetype, we can just zip two iterators over component storages and skip the index
     /// for the removed/added type
-    pub(crate) component_storages: Vec<(EcsId, UnsafeCell<UntypedVec>)>, // We need the EcsId here so that we can sort the vec :( the EcsId here should be the same as the one in comp_ids at the same index
+    pub(crate) component_storages: Vec<(EcsId, UnsafeCell<Column>)>, // We need the EcsId here so that we can sort the vec :( the EcsId here should be the same as the one in comp_ids at the same index
 
     /// The order of this vec is guaranteed to be the same as the order of component storages,
     /// this means that you can .iter().position(|id| ...) to find the index in component_storages for an EcsId
     pub(crate) comp_ids: Vec<EcsId>,
 
-    pub(crate) add_remove_cache: AddRemoveCache,
+    /// The archetypes reached by adding a component to this archetype
+    pub(crate) add_edges: ArchetypeEdges,
+    /// The archetypes reached by removing a component from this archetype
+    pub(crate) remove_edges: ArchetypeEdges,
 }
 
 impl Archetype {
@@ -89,12 +254,13 @@ impl Archetype {
                 // Capacity + 1 incase this gets fed into a from_archetype_with call
                 let mut storages = Vec::with_capacity(from.component_storages.len() + 1);
                 for storage in from.component_storages.iter_mut() {
-                    let untyped_vec = UntypedVec::new_from_untyped_vec(storage.1.get_mut());
-                    storages.push((storage.0, UnsafeCell::new(untyped_vec)));
+                    let column = Column::new_from_column(storage.1.get_mut());
+                    storages.push((storage.0, UnsafeCell::new(column)));
                 }
                 storages
             },
-            add_remove_cache: AddRemoveCache::new(),
+            add_edges: ArchetypeEdges::new(),
+            remove_edges: ArchetypeEdges::new(),
         }
     }
 
@@ -104,7 +270,7 @@ impl Archetype {
     #[allow(unused_unsafe)]
     pub unsafe fn from_archetype_with(
         from: &mut Archetype,
-        with_type_info: untyped_vec::TypeInfo,
+        with_type_info: TypeInfo,
         with_id: EcsId,
     ) -> Archetype {
         let mut new_archetype = Archetype::from_archetype(from);
@@ -114,7 +280,7 @@ impl Archetype {
         new_archetype.comp_ids.push(with_id);
         new_archetype.component_storages.push((
             with_id,
-            UnsafeCell::new(unsafe { UntypedVec::new_from_raw(with_type_info) }),
+            UnsafeCell::new(unsafe { Column::new(with_type_info) }),
         ));
 
         // TODO there's no need to sort twice they should have the same ordering
@@ -198,12 +364,19 @@ impl Archetype {
         false
     }
 
-    pub fn try_find_next_archetype(&mut self, id: EcsId) -> Option<usize> {
-        self.add_remove_cache.lookup_id(id)
-    }
-
-    pub fn insert_archetype_cache(&mut self, id: EcsId, archetype: usize) {
-        self.add_remove_cache.insert_id(id, archetype);
+    /// Pointer to the start of the added ticks for ``comp_id``, or of the changed ticks if ``added`` is false, null if
+    /// this archetype doesn't have the component
+    pub(crate) fn get_ticks_ptr(&self, comp_id: EcsId, added: bool) -> *mut u32 {
+        match self.comp_lookup.get(&comp_id) {
+            Some(&storage_idx) => {
+                let column = unsafe { &mut *self.component_storages[storage_idx].1.get() };
+                match added {
+                    true => column.added.as_mut_ptr(),
+                    false => column.changed.as_mut_ptr(),
+                }
+            }
+            None => core::ptr::null_mut(),
+        }
     }
 }
 
@@ -215,40 +388,102 @@ pub struct EntityMeta {
     pub component_meta: ComponentMeta,
 }
 
+/// The index of an archetype, only valid until ``World::compact`` moves the archetypes around so it can't be read
+/// outside of the crate
 #[derive(Clone, Debug)]
-pub struct ArchIndex(pub usize);
+pub struct ArchIndex(pub(crate) usize);
+/// Where an entity is stored, see ``ArchIndex``
 #[derive(Clone, Debug)]
 pub struct InstanceMeta {
-    pub archetype: ArchIndex,
-    pub index: usize,
+    pub(crate) archetype: ArchIndex,
+    pub(crate) index: usize,
 }
 
+pub type DebugFn = fn(*const u8, &mut std::fmt::Formatter<'_>) -> std::fmt::Result;
+
 #[derive(Clone, Debug)]
 pub struct ComponentMeta {
     pub drop_fn: Option<fn(*mut core::mem::MaybeUninit<u8>)>,
     pub layout: core::alloc::Layout,
     pub is_unit: bool,
+    /// The name of the type the component was created for, None for components created without a type
+    pub type_name: Option<&'static str>,
+    /// Formats the component with its ``Debug`` impl, see ``World::debug_string``
+    pub debug_fn: Option<DebugFn>,
+    /// Calls ``MapEntities::map_entities`` on the component, see ``World::merge_from``
+    pub map_entities_fn: Option<fn(*mut u8, &crate::EntityMap)>,
+    /// Writes a clone of the component to a destination that does not need to be aligned, see ``World::clone_entity``
+    pub clone_fn: Option<fn(*const u8, *mut core::mem::MaybeUninit<u8>)>,
 }
 
 fn component_meta_drop_fn<T: Component>(ptr: *mut core::mem::MaybeUninit<u8>) {
     unsafe { core::ptr::drop_in_place::<T>(ptr as *mut T) }
 }
 
+fn component_meta_debug_fn<T: Component + std::fmt::Debug>(
+    ptr: *const u8,
+    f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
+    std::fmt::Debug::fmt(unsafe { &*(ptr as *const T) }, f)
+}
+
+fn component_meta_clone_fn<T: Component + Clone>(
+    src: *const u8,
+    dst: *mut core::mem::MaybeUninit<u8>,
+) {
+    unsafe { core::ptr::write_unaligned(dst as *mut T, (*(src as *const T)).clone()) }
+}
+
+/// Formats a component through the ``DebugFn`` of its meta
+struct DebugComponent(*const u8, DebugFn);
+
+impl std::fmt::Debug for DebugComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self.1)(self.0, f)
+    }
+}
+
 impl ComponentMeta {
     pub fn from_size_align(size: usize, align: usize) -> Self {
         Self {
             drop_fn: None,
             layout: core::alloc::Layout::from_size_align(size, align).unwrap(),
             is_unit: false,
+            type_name: None,
+            debug_fn: None,
+            map_entities_fn: None,
+            clone_fn: None,
         }
     }
 
-    /// Creates a ComponentMeta with the layout and drop_fn of the generic
+    /// Creates a ComponentMeta with the layout, drop_fn and type name of the generic
     pub fn from_generic<T: Component>() -> Self {
         Self {
             drop_fn: Some(component_meta_drop_fn::<T>),
             layout: core::alloc::Layout::new::<T>(),
             is_unit: TypeId::of::<T>() == TypeId::of::<()>(),
+            type_name: Some(std::any::type_name::<T>()),
+            debug_fn: None,
+            map_entities_fn: None,
+            clone_fn: None,
+        }
+    }
+
+    /// Same as ``ComponentMeta::from_generic`` but components are also formatted with ``Debug`` by
+    /// ``World::debug_string``
+    pub fn from_generic_debug<T: Component + std::fmt::Debug>() -> Self {
+        Self {
+            debug_fn: Some(component_meta_debug_fn::<T>),
+            ..Self::from_generic::<T>()
+        }
+    }
+
+    /// Same as ``ComponentMeta::from_generic`` but components can also be cloned by ``World::clone_entity`` and
+    /// ``World::try_clone``
+    pub fn from_generic_clone<T: Component + Clone>() -> Self {
+        Self {
+            clone_fn: Some(component_meta_clone_fn::<T>),
+            ..Self::from_generic::<T>()
         }
     }
 
@@ -258,23 +493,52 @@ impl ComponentMeta {
             drop_fn: None,
             layout: core::alloc::Layout::new::<()>(),
             is_unit: true,
+            type_name: None,
+            debug_fn: None,
+            map_entities_fn: None,
+            clone_fn: None,
         }
     }
 }
 
+/// ``World`` is deliberately not ``Sync``, the per-component locks allow any number of readers so sharing a ``&World``
+/// between threads would let two threads read a ``!Sync`` component such as ``Rc`` at the same time. Parallel iteration
+/// goes through ``StaticQuery::par_iter`` instead which only sends the locked rows to other threads and requires every
+/// item it hands out to be ``Send``
 pub struct World {
     pub(crate) archetypes: Vec<Archetype>,
+    /// A lookup of an archetype's sorted comp_ids to its index into archetypes, archetypes emptied out by
+    /// ``World::remove_archetype_with_dead_component`` are not in the lookup
+    pub(crate) archetype_lookup:
+        HashMap<Vec<EcsId>, ArchIndex, crate::utils::ComponentSetHasherBuilder>,
     pub(crate) archetype_bitset: Bitsetsss,
     pub(crate) entities_bitvec: Bitvec,
 
-    entities: Entities,
+    pub(crate) entities: Entities,
 
-    ecs_id_meta: Vec<Option<EntityMeta>>,
+    pub(crate) ecs_id_meta: Vec<Option<EntityMeta>>,
     pub(crate) type_id_to_ecs_id: HashMap<TypeId, EcsId, crate::utils::TypeIdHasherBuilder>,
 
     pub(crate) lock_lookup: HashMap<EcsId, usize, crate::utils::TypeIdHasherBuilder>,
     pub(crate) locks: Vec<RwLock<()>>,
 
+    /// Tick that added and mutably accessed components are marked with, starts at 1 so that a last run tick of 0
+    /// treats every component as added and changed
+    pub(crate) change_tick: AtomicU32,
+
+    /// Entities that had a component removed or were despawned while having it, keyed by the component's id.
+    /// Cleared with ``World::clear_removed``
+    pub(crate) removed_components: HashMap<EcsId, Vec<EcsId>, crate::utils::TypeIdHasherBuilder>,
+    /// Entities that were despawned, cleared with ``World::clear_despawned``
+    pub(crate) despawned: Vec<EcsId>,
+
+    /// The entity that resources are stored on as components, spawned by the first ``World::insert_resource``
+    pub(crate) resource_entity: Option<EcsId>,
+
+    /// Components that can be saved and loaded, see ``World::save``
+    #[cfg(feature = "serde")]
+    pub(crate) component_registry: crate::serialize::ComponentRegistry,
+
     /// usize is that cap allocated with the pointer
     pub(crate) entity_builder_reuse: Option<(Vec<EcsId>, core::ptr::NonNull<u8>, usize)>,
 }
@@ -302,6 +566,7 @@ impl World {
     pub fn new() -> Self {
         Self {
             archetypes: Vec::new(),
+            archetype_lookup: HashMap::with_hasher(crate::utils::ComponentSetHasherBuilder()),
             archetype_bitset: Bitsetsss::with_capacity(32),
             entities_bitvec: Bitvec::with_capacity(32),
 
@@ -316,6 +581,16 @@ impl World {
             lock_lookup: HashMap::with_hasher(crate::utils::TypeIdHasherBuilder()),
             locks: Vec::new(),
 
+            change_tick: AtomicU32::new(1),
+
+            removed_components: HashMap::with_hasher(crate::utils::TypeIdHasherBuilder()),
+            despawned: Vec::new(),
+
+            resource_entity: None,
+
+            #[cfg(feature = "serde")]
+            component_registry: crate::serialize::ComponentRegistry::new(),
+
             entity_builder_reuse: None,
         }
     }
@@ -323,6 +598,7 @@ impl World {
     #[must_use]
     /// Creates an entity builder for creating an entity. See the spawn!() macro for a more concise way to use the EntityBuilder
     pub fn spawn(&mut self) -> crate::entity_builder::EntityBuilder {
+        self.flush_reserved();
         let entity = self.entities.spawn();
         crate::entity_builder::EntityBuilder::new(self, entity, ComponentMeta::unit())
     }
@@ -330,6 +606,7 @@ impl World {
     #[must_use]
     /// Same as ``World::spawn`` except takes a capacity to initialise the component storage to
     pub fn spawn_with_capacity(&mut self, capacity: usize) -> crate::entity_builder::EntityBuilder {
+        self.flush_reserved();
         let entity = self.entities.spawn();
         crate::entity_builder::EntityBuilder::with_capacity(
             self,
@@ -339,259 +616,937 @@ impl World {
         )
     }
 
+    /// Reserves an EcsId without needing mutable access to the world, the entity is spawned with no components the next time
+    /// the world is mutably accessed. Reserved entities are considered alive before being spawned
+    pub fn reserve_entity(&self) -> EcsId {
+        self.entities.reserve()
+    }
+
+    /// Same as ``World::reserve_entity`` but reserves ``count`` ids at once
+    pub fn reserve_entities(&self, count: usize) -> impl Iterator<Item = EcsId> + '_ {
+        self.entities.reserve_many(count)
+    }
+
     /// Despawns an entity, if the entity being despawned is added as a component to any entities it will be automatically removed
+    ///
+    /// Returns false if the entity is dead or is the resource entity, resources are removed with
+    /// ``World::remove_resource``
     pub fn despawn(&mut self, entity: EcsId) -> bool {
-        if !self.entities.is_alive(entity) {
+        self.flush_reserved();
+        if !self.entities.is_alive(entity) || self.resource_entity == Some(entity) {
             return false;
         }
 
-        // TODO: Remove `entity` component from all entities
-        for arch in &self.archetypes {
-            if let Some(_) = arch.comp_lookup.get(&entity) {
-                todo!();
-            }
-        }
+        #[cfg(feature = "hierarchy")]
+        self.detach_from_hierarchy(entity);
 
         let InstanceMeta { archetype, index } =
             self.get_entity_meta(entity).unwrap().instance_meta.clone();
 
+        for &comp_id in self.archetypes[archetype.0].comp_ids.iter() {
+            self.removed_components
+                .entry(comp_id)
+                .or_default()
+                .push(entity);
+        }
         self.archetypes[archetype.0].despawn(entity, index, &mut self.ecs_id_meta);
+
+        // Pairs with the entity as their relation or target die along with it
+        let dead_pairs = self.archetype_bitset.pair_ids_of(entity.uindex()).to_vec();
+        for &dead_id in std::iter::once(&entity).chain(dead_pairs.iter()) {
+            let dead_archetypes = (0..self.archetypes.len())
+                .filter(|&idx| self.archetypes[idx].comp_lookup.contains_key(&dead_id))
+                .collect::<Vec<_>>();
+            for archetype_idx in dead_archetypes {
+                self.remove_archetype_with_dead_component(ArchIndex(archetype_idx), dead_id);
+            }
+
+            self.lock_lookup.remove(&dead_id);
+            // A dead component can't be looked up anymore so there's no point keeping its removal log
+            self.removed_components.remove(&dead_id);
+        }
+        for pair in dead_pairs {
+            self.archetype_bitset.remove_pair_bitvec(pair);
+            // Pairs don't have generations so a pair with a new entity in place of a dead one has the same id and
+            // must not follow edges to the archetypes removed above
+            for archetype in self.archetypes.iter_mut() {
+                archetype.add_edges.remove_id(pair);
+            }
+        }
+        self.type_id_to_ecs_id.retain(|_, id| *id != entity);
+
         self.entities.despawn(entity);
+        self.despawned.push(entity);
         true
     }
 
-    pub fn is_alive(&self, entity: EcsId) -> bool {
-        self.entities.is_alive(entity)
-    }
-
-    pub fn query_dynamic<const N: usize>(&self, ids: [FetchType; N]) -> DynQuery<'_, N> {
-        DynQuery::new(self, ids)
+    /// Returns every entity that had the component ``T`` removed, or was despawned while having it, since the last
+    /// call to ``World::clear_removed``
+    pub fn removed<T: Component>(&self) -> &[EcsId] {
+        match self.type_id_to_ecs_id.get(&TypeId::of::<T>()) {
+            Some(&comp_id) => self.removed_dynamic(comp_id),
+            None => &[],
+        }
     }
 
-    pub fn query<'a, Q: crate::static_query::QueryTuple>(&'a self) -> StaticQuery<'a, Q> {
-        Q::new(self)
+    /// Same as ``World::removed`` but for any component id
+    pub fn removed_dynamic(&self, comp_id: EcsId) -> &[EcsId] {
+        match self.removed_components.get(&comp_id) {
+            Some(removed) => removed.as_slice(),
+            None => &[],
+        }
     }
 
-    pub fn add_component<T: Component>(&mut self, entity: EcsId, component: T) {
-        assert!(self.entities.is_alive(entity));
-        let comp_id = self.get_or_create_type_id_ecsid::<T>();
-        let mut component = core::mem::ManuallyDrop::new(component);
-        unsafe {
-            self.add_component_dynamic_with_data(
-                entity,
-                comp_id,
-                &mut component as *mut _ as *mut u8,
-            );
+    /// Clears the removed components log of every component
+    pub fn clear_removed(&mut self) {
+        for removed in self.removed_components.values_mut() {
+            removed.clear();
         }
     }
 
-    pub fn remove_component<T: Component>(&mut self, entity: EcsId) {
-        assert!(self.entities.is_alive(entity));
-        let comp_id = self.get_or_create_type_id_ecsid::<T>();
-        self.remove_component_dynamic(entity, comp_id);
+    /// Returns every entity that was despawned since the last call to ``World::clear_despawned``
+    pub fn despawned(&self) -> &[EcsId] {
+        &self.despawned
     }
 
-    pub fn has_component<T: Component>(&self, entity: EcsId) -> bool {
-        let func = || {
-            let comp_id = self.type_id_to_ecs_id.get(&TypeId::of::<T>())?;
-            let ArchIndex(idx) = self.get_entity_meta(entity)?.instance_meta.archetype;
-            Some(self.archetypes[idx].comp_lookup.get(comp_id).is_some())
-        };
-
-        func().unwrap_or(false)
+    pub fn clear_despawned(&mut self) {
+        self.despawned.clear();
     }
 
-    /// Adds an entity as a dataless component
+    /// Removes every archetype without any entities and shrinks the component storages of the remaining ones
     ///
-    /// This method will panic if a component with the ID of component_id expects data. Entities by default expect no data.
-    pub fn add_component_dynamic(&mut self, entity: EcsId, component_id: EcsId) {
-        assert!(self.entities.is_alive(entity));
-        assert!(self.entities.is_alive(component_id));
-        assert!(
-            self.get_entity_meta(component_id)
-                .unwrap()
-                .component_meta
-                .is_unit
-        );
-
-        unsafe {
-            self.add_component_dynamic_with_data(entity, component_id, 0x1 as *mut u8);
+    /// Archetypes are moved to new indices, every ``ArchIndex`` stored in the world is remapped
+    pub fn compact(&mut self) {
+        self.flush_reserved();
+
+        // The archetype with no components is kept even if it is empty as every entity starts out in it
+        let empty_archetype = self.archetype_lookup.get(&[][..]).map(|idx| idx.0);
+        let mut remap = Vec::with_capacity(self.archetypes.len());
+        let mut kept = 0;
+        for (idx, archetype) in self.archetypes.iter().enumerate() {
+            if archetype.entities.is_empty() && Some(idx) != empty_archetype {
+                remap.push(None);
+            } else {
+                remap.push(Some(kept));
+                kept += 1;
+            }
         }
-    }
-}
-
-impl World {
-    #[must_use]
-    /// # Safety
-    ///
-    ///    All subsequent uses of this entity as a component must be valid for the given ComponentMeta
-    pub unsafe fn spawn_with_component_meta(
-        &mut self,
-        component_meta: ComponentMeta,
-    ) -> crate::entity_builder::EntityBuilder {
-        let entity = self.entities.spawn();
 
-        crate::entity_builder::EntityBuilder::new(self, entity, component_meta)
-    }
+        let archetypes = std::mem::take(&mut self.archetypes);
+        self.archetype_lookup.clear();
+        self.archetype_bitset = Bitsetsss::with_capacity(32);
+        self.entities_bitvec = Bitvec::with_capacity(kept);
+        for (mut archetype, new_idx) in archetypes.into_iter().zip(remap.iter()) {
+            if new_idx.is_none() {
+                continue;
+            }
 
-    pub fn get_or_create_type_id_ecsid<T: Component>(&mut self) -> EcsId {
-        let comp_id = self.type_id_to_ecs_id.get(&TypeId::of::<T>());
-        if let Some(comp_id) = comp_id {
-            return *comp_id;
+            archetype.add_edges.remap(&remap);
+            archetype.remove_edges.remap(&remap);
+            archetype.entities.shrink_to_fit();
+            for (_, storage) in archetype.component_storages.iter_mut() {
+                storage.get_mut().shrink_to_fit();
+            }
+            self.push_archetype(archetype);
         }
 
-        let entity = self.spawn().build();
-
-        // Guaranteed valid because we just spawned the entity
-        let meta = self.ecs_id_meta[entity.uindex()].as_mut().unwrap();
-        meta.component_meta = ComponentMeta::from_generic::<T>();
-
-        self.type_id_to_ecs_id.insert(TypeId::of::<T>(), entity);
+        for meta in self.ecs_id_meta.iter_mut().flatten() {
+            let archetype = &mut meta.instance_meta.archetype;
+            *archetype = ArchIndex(remap[archetype.0].unwrap());
+        }
+    }
 
-        entity
+    /// Prints every archetype along with its components and entities, see ``World::debug_string``
+    pub fn dump_debug(&self) {
+        print!("{}", self.debug_string());
     }
 
-    pub fn get_entity_meta(&self, entity: EcsId) -> Option<&EntityMeta> {
-        if !self.entities.is_alive(entity) {
-            return None;
-        }
+    /// Lists every archetype with its row count and component ids along with the type names of the ids, followed by
+    /// each of its entities and their components. Components of types registered with ``World::register_debug`` are
+    /// formatted with ``Debug``, components that are mutably borrowed by a query are shown as ``<borrowed>``
+    pub fn debug_string(&self) -> String {
+        use std::fmt::Write;
+        let mut out = String::new();
+        for (idx, archetype) in self.archetypes.iter().enumerate() {
+            // Skips the archetypes emptied out by ``World::remove_archetype_with_dead_component``
+            if self
+                .archetype_lookup
+                .get(&archetype.comp_ids)
+                .map(|idx| idx.0)
+                != Some(idx)
+            {
+                continue;
+            }
 
-        self.ecs_id_meta.get(entity.uindex())?.as_ref()
-    }
+            writeln!(out, "archetype {}: {} rows", idx, archetype.entities.len()).unwrap();
+            let names = archetype
+                .comp_ids
+                .iter()
+                .map(|&id| self.component_debug_name(id))
+                .collect::<Vec<_>>();
+            for (id, name) in archetype.comp_ids.iter().zip(names.iter()) {
+                writeln!(out, "  component {}: {}", id, name).unwrap();
+            }
 
-    pub(crate) fn set_entity_meta(&mut self, entity: EcsId, meta: EntityMeta) {
-        if self.entities.is_alive(entity) {
-            let new_meta = Some(meta);
-            match self.ecs_id_meta.get_mut(entity.uindex()) {
-                Some(old_meta) => *old_meta = new_meta,
-                None => {
-                    self.ecs_id_meta.resize_with(entity.uindex(), || None);
-                    self.ecs_id_meta.push(new_meta);
+            let guards = archetype
+                .comp_ids
+                .iter()
+                .map(|&id| self.try_lock(id, false).ok())
+                .collect::<Vec<_>>();
+            for (row, entity) in archetype.entities.iter().enumerate() {
+                write!(out, "  entity {}:", entity).unwrap();
+                for (n, (id, column)) in archetype.component_storages.iter().enumerate() {
+                    let separator = if n == 0 { "" } else { "," };
+                    write!(out, "{} {}", separator, names[n]).unwrap();
+                    let meta = self.get_component_meta(*id).unwrap();
+                    match (meta.is_unit, &guards[n], meta.debug_fn) {
+                        (true, _, _) => (),
+                        (false, None, _) => write!(out, " = <borrowed>").unwrap(),
+                        (false, Some(_), Some(debug_fn)) => {
+                            // Safe because the read lock is held so nothing is writing to the column
+                            let ptr = unsafe { (*column.get()).data.get_raw(row).unwrap() };
+                            write!(out, " = {:?}", DebugComponent(ptr, debug_fn)).unwrap();
+                        }
+                        (false, Some(_), None) => write!(out, " = ..").unwrap(),
+                    }
                 }
+                writeln!(out).unwrap();
             }
         }
+        out
     }
 
-    pub(crate) fn query_archetypes<'a, const N: usize>(
-        &'a self,
-        iters: [(Iter<'a, usize>, fn(usize) -> usize); N],
-        bit_length: u32,
-    ) -> ArchetypeIter<'a, N> {
-        ArchetypeIter {
-            archetypes: &self.archetypes,
-            iter: BitsetIterator::new(iters, bit_length),
+    /// The type name of a component, or its id if it wasn't created for a type
+    fn component_debug_name(&self, comp_id: EcsId) -> String {
+        if comp_id.is_pair() {
+            let relation = self.entities.alive_at(comp_id.relation_index()).unwrap();
+            let target = self.entities.alive_at(comp_id.uindex()).unwrap();
+            return format!("({}, {})", self.component_debug_name(relation), target);
         }
-    }
 
-    pub(crate) fn find_archetype_dynamic(&mut self, comp_ids: &[EcsId]) -> Option<ArchIndex> {
-        if self.archetypes.len() > 0 && comp_ids.len() == 0 {
-            assert!(self.archetypes[0].comp_ids.len() == 0);
-            return Some(ArchIndex(0));
+        match self
+            .get_component_meta(comp_id)
+            .and_then(|meta| meta.type_name)
+        {
+            Some(type_name) => type_name.to_string(),
+            None => format!("{}", comp_id),
         }
+    }
 
-        let mut bit_length = u32::MAX;
-        let identity: fn(_) -> _ = |x: usize| x;
+    /// Makes the component ``T`` cloneable by ``World::clone_entity`` and ``World::try_clone``
+    pub fn register_clone<T: Component + Clone>(&mut self) {
+        let comp_id = self.get_or_create_type_id_ecsid::<T>();
+        let meta = self.ecs_id_meta[comp_id.uindex()].as_mut().unwrap();
+        meta.component_meta.clone_fn = Some(component_meta_clone_fn::<T>);
+    }
 
-        for id in comp_ids {
-            let bitvec = self.archetype_bitset.get_bitvec(*id)?;
-            if bitvec.len < bit_length as _ {
-                bit_length = bitvec.len as _;
-            }
-        }
+    /// Makes ``World::debug_string`` format the component ``T`` with ``Debug``
+    pub fn register_debug<T: Component + std::fmt::Debug>(&mut self) {
+        let comp_id = self.get_or_create_type_id_ecsid::<T>();
+        let meta = self.ecs_id_meta[comp_id.uindex()].as_mut().unwrap();
+        meta.component_meta.debug_fn = Some(component_meta_debug_fn::<T>);
+    }
 
-        let iters = comp_ids
-            .iter()
-            .map(|&id| self.archetype_bitset.get_bitvec(id).unwrap().data.iter())
-            .map(|bitvec| (bitvec, identity))
-            .collect::<Box<[_]>>();
+    pub fn is_alive(&self, entity: EcsId) -> bool {
+        self.entities.is_alive(entity)
+    }
 
-        BitsetIterator::new(iters, bit_length)
-            .filter(|idx| self.archetypes[*idx].comp_ids.len() == comp_ids.len())
-            .next()
-            .map(ArchIndex)
+    /// The tick that components are currently being marked as added/changed with
+    pub fn change_tick(&self) -> u32 {
+        self.change_tick.load(Ordering::Relaxed)
     }
 
-    pub(crate) fn find_archetype_dynamic_plus_id(
-        &self,
-        comp_ids: &[EcsId],
-        extra_id: EcsId,
-    ) -> Option<usize> {
-        let identity: fn(_) -> _ = |x: usize| x;
+    /// Advances the change tick and returns the new tick, anything added or changed from now on will be seen by
+    /// ``Added`` and ``Changed`` filters that were given a last run tick from before this call
+    pub fn increment_change_tick(&self) -> u32 {
+        self.change_tick.fetch_add(1, Ordering::Relaxed) + 1
+    }
 
-        let mut bit_length = u32::MAX;
-        for id in comp_ids.iter().chain(std::iter::once(&extra_id)) {
-            let bitvec = self.archetype_bitset.get_bitvec(*id)?;
-            if bitvec.len < bit_length as _ {
-                bit_length = bitvec.len as _;
-            }
-        }
+    /// Applies all of the commands recorded in ``buffer`` in the order they were recorded, this leaves ``buffer`` empty.
+    /// Commands for dead entities, adding a component that is already present and removing one that is missing are
+    /// skipped, the data of a component that couldn't be added is dropped
+    pub fn apply(&mut self, buffer: &mut CommandBuffer) {
+        self.flush_reserved();
+        buffer.apply(self);
+    }
 
-        let iters = comp_ids
-            .iter()
-            .map(|&id| self.archetype_bitset.get_bitvec(id).unwrap().data.iter())
-            .map(|iter| (iter, identity))
-            .chain(std::iter::once((
-                self.archetype_bitset
-                    .get_bitvec(extra_id)
-                    .unwrap()
-                    .data
-                    .iter(),
-                identity,
-            )))
-            .collect::<Box<[_]>>();
-
-        BitsetIterator::new(iters, bit_length)
-            .filter(|idx| self.archetypes[*idx].comp_ids.len() == comp_ids.len() + 1)
-            .next()
+    pub fn query_dynamic<const N: usize>(&self, ids: [FetchType; N]) -> DynQuery<'_, N> {
+        DynQuery::new(self, ids)
     }
 
-    pub(crate) fn find_archetype_dynamic_minus_id(
-        &self,
-        comp_ids: &[EcsId],
-        without_id: EcsId,
-    ) -> Option<usize> {
-        if self.archetypes.len() > 0 && comp_ids.len() == 1 {
-            assert!(self.archetypes[0].comp_ids.len() == 0);
-            return Some(0);
-        }
+    /// Panicking version of ``World::try_query``
+    pub fn query<'a, Q: crate::static_query::QueryTuple>(&'a self) -> StaticQuery<'a, Q> {
+        Q::new(self)
+    }
+
+    /// Creates a query, fails instead of blocking if the query borrows a component mutably more than once or if
+    /// a component is already borrowed by a query, ``Res`` or ``ResMut`` in a way that conflicts with this query
+    pub fn try_query<'a, Q: crate::static_query::QueryTuple>(
+        &'a self,
+    ) -> Result<StaticQuery<'a, Q>, BorrowError> {
+        Q::try_new(self)
+    }
 
-        let identity: fn(_) -> _ = |x: usize| x;
+    /// Locks the component ``comp_id`` without blocking. Components that were registered but never added to an
+    /// entity have no lock, queries won't match any archetypes with them so there is nothing to lock
+    pub(crate) fn try_lock(
+        &self,
+        comp_id: EcsId,
+        write: bool,
+    ) -> Result<EitherGuard<'_>, BorrowError> {
+        let lock = match self.lock_lookup.get(&comp_id) {
+            Some(&idx) => &self.locks[idx],
+            None => return Ok(EitherGuard::None),
+        };
 
-        let mut bit_length = u32::MAX;
-        for id in comp_ids.iter().filter(|&&id| id != without_id) {
-            let bitvec = self.archetype_bitset.get_bitvec(*id)?;
-            if bitvec.len < bit_length as _ {
-                bit_length = bitvec.len as _;
+        if write {
+            match lock.try_write() {
+                Ok(guard) => Ok(EitherGuard::Write(guard)),
+                Err(TryLockError::WouldBlock) => Err(BorrowError::Conflict(comp_id)),
+                Err(TryLockError::Poisoned(err)) => panic!("{}", err),
+            }
+        } else {
+            match lock.try_read() {
+                Ok(guard) => Ok(EitherGuard::Read(guard)),
+                Err(TryLockError::WouldBlock) => Err(BorrowError::Conflict(comp_id)),
+                Err(TryLockError::Poisoned(err)) => panic!("{}", err),
             }
         }
+    }
 
-        let iters = comp_ids
-            .iter()
-            .filter(|&&id| id != without_id)
-            .map(|&id| self.archetype_bitset.get_bitvec(id).unwrap().data.iter())
-            .map(|iter| (iter, identity))
-            .collect::<Box<[_]>>();
+    /// Panicking version of ``World::try_add``
+    pub fn add_component<T: Component>(&mut self, entity: EcsId, component: T) {
+        self.try_add(entity, component).unwrap();
+    }
 
-        BitsetIterator::new(iters, bit_length)
-            .filter(|idx| self.archetypes[*idx].comp_ids.len() == comp_ids.len() - 1)
-            .next()
+    /// Panicking version of ``World::try_remove``
+    pub fn remove_component<T: Component>(&mut self, entity: EcsId) {
+        self.try_remove::<T>(entity).unwrap();
     }
 
-    /// # Safety
-    ///
-    ///   ``component_ptr`` must point to data that matches the component_meta of component_id.
-    ///   The data must also not be used after calling this function.
+    /// Adds ``component`` to ``entity``, if the entity already has a ``T`` the component is dropped and an error is returned
+    pub fn try_add<T: Component>(&mut self, entity: EcsId, component: T) -> Result<(), EcsError> {
+        self.flush_reserved();
+        if !self.entities.is_alive(entity) {
+            return Err(EcsError::NoSuchEntity);
+        }
+        let comp_id = self.get_or_create_type_id_ecsid::<T>();
+        let mut component = core::mem::ManuallyDrop::new(component);
+        let result = unsafe {
+            self.try_add_component_dynamic_with_data(
+                entity,
+                comp_id,
+                &mut *component as *mut T as *mut u8,
+            )
+        };
+        if result.is_err() {
+            // The component is still owned by us if it couldn't be added
+            unsafe { core::mem::ManuallyDrop::drop(&mut component) };
+        }
+        result
+    }
+
+    /// Adds ``component`` to ``entity``, overwriting the existing ``T`` in place if the entity already has one
+    pub fn insert<T: Component>(&mut self, entity: EcsId, component: T) -> Result<(), EcsError> {
+        self.flush_reserved();
+        if !self.entities.is_alive(entity) {
+            return Err(EcsError::NoSuchEntity);
+        }
+        let comp_id = self.get_or_create_type_id_ecsid::<T>();
+        let mut component = core::mem::ManuallyDrop::new(component);
+        let result = unsafe {
+            self.insert_dynamic_with_data(entity, comp_id, &mut *component as *mut T as *mut u8)
+        };
+        if result.is_err() {
+            unsafe { core::mem::ManuallyDrop::drop(&mut component) };
+        }
+        result
+    }
+
+    /// Removes the component ``T`` from ``entity``, the component is dropped
+    pub fn try_remove<T: Component>(&mut self, entity: EcsId) -> Result<(), EcsError> {
+        self.flush_reserved();
+        if !self.entities.is_alive(entity) {
+            return Err(EcsError::NoSuchEntity);
+        }
+        match self.type_id_to_ecs_id.get(&TypeId::of::<T>()) {
+            Some(&comp_id) => self.try_remove_component_dynamic(entity, comp_id),
+            // Nothing can have a component of an unregistered type
+            None => Err(EcsError::Missing),
+        }
+    }
+
+    /// Panicking version of ``World::try_add_pair``
+    pub fn add_pair<R: Component>(&mut self, entity: EcsId, relation: R, target: EcsId) {
+        self.try_add_pair(entity, relation, target).unwrap();
+    }
+
+    /// Panicking version of ``World::try_remove_pair``
+    pub fn remove_pair<R: Component>(&mut self, entity: EcsId, target: EcsId) {
+        self.try_remove_pair::<R>(entity, target).unwrap();
+    }
+
+    /// Adds the pair ``(R, target)`` to ``entity`` with ``relation`` as its data, see ``EcsId::pair``. If the entity
+    /// already has the pair ``relation`` is dropped and an error is returned
+    pub fn try_add_pair<R: Component>(
+        &mut self,
+        entity: EcsId,
+        relation: R,
+        target: EcsId,
+    ) -> Result<(), EcsError> {
+        self.flush_reserved();
+        if !self.entities.is_alive(entity) || !self.entities.is_alive(target) {
+            return Err(EcsError::NoSuchEntity);
+        }
+        let pair = self.pair_id::<R>(target);
+        let mut relation = core::mem::ManuallyDrop::new(relation);
+        let result = unsafe {
+            self.try_add_component_dynamic_with_data(
+                entity,
+                pair,
+                &mut *relation as *mut R as *mut u8,
+            )
+        };
+        if result.is_err() {
+            unsafe { core::mem::ManuallyDrop::drop(&mut relation) };
+        }
+        result
+    }
+
+    /// Removes the pair ``(R, target)`` from ``entity``, the relation's data is dropped
+    pub fn try_remove_pair<R: Component>(
+        &mut self,
+        entity: EcsId,
+        target: EcsId,
+    ) -> Result<(), EcsError> {
+        self.flush_reserved();
+        if !self.entities.is_alive(entity) || !self.entities.is_alive(target) {
+            return Err(EcsError::NoSuchEntity);
+        }
+        match self.type_id_to_ecs_id.get(&TypeId::of::<R>()) {
+            Some(&relation) => {
+                self.try_remove_component_dynamic(entity, EcsId::pair(relation, target))
+            }
+            None => Err(EcsError::Missing),
+        }
+    }
+
+    /// The id of the pair ``(R, target)``, ``R`` is registered as a component if it hasn't been already
+    pub fn pair_id<R: Component>(&mut self, target: EcsId) -> EcsId {
+        EcsId::pair(self.get_or_create_type_id_ecsid::<R>(), target)
+    }
+
+    /// Every target of the pairs with the relation ``R`` that ``entity`` has
+    pub fn targets<R: Component>(&self, entity: EcsId) -> Vec<EcsId> {
+        match self.type_id_to_ecs_id.get(&TypeId::of::<R>()) {
+            Some(&relation) => self.targets_dynamic(entity, relation),
+            None => Vec::new(),
+        }
+    }
+
+    /// Same as ``World::targets`` but for any relation
+    pub fn targets_dynamic(&self, entity: EcsId, relation: EcsId) -> Vec<EcsId> {
+        let archetype = match self.get_entity_meta(entity) {
+            Some(meta) => &self.archetypes[meta.instance_meta.archetype.0],
+            None => return Vec::new(),
+        };
+        archetype
+            .comp_ids
+            .iter()
+            .filter(|id| id.is_pair() && id.relation_index() == relation.uindex())
+            .filter_map(|pair| self.entities.alive_at(pair.uindex()))
+            .collect()
+    }
+
+    /// Panicking version of ``World::try_add_bundle``
+    pub fn add_bundle<B: Bundle>(&mut self, entity: EcsId, bundle: B) {
+        self.try_add_bundle(entity, bundle).unwrap();
+    }
+
+    /// Panicking version of ``World::try_remove_bundle``
+    pub fn remove_bundle<B: Bundle>(&mut self, entity: EcsId) {
+        self.try_remove_bundle::<B>(entity).unwrap();
+    }
+
+    /// Adds every component in ``bundle`` to ``entity`` by moving it straight to the final archetype, if the entity
+    /// already has any of the components nothing is added and the bundle is dropped
+    ///
+    /// This method will panic if the same component type is in the bundle more than once
+    pub fn try_add_bundle<B: Bundle>(&mut self, entity: EcsId, bundle: B) -> Result<(), EcsError> {
+        self.flush_reserved();
+        if !self.entities.is_alive(entity) {
+            return Err(EcsError::NoSuchEntity);
+        }
+        let comp_ids = B::comp_ids(self);
+        let mut bundle = core::mem::ManuallyDrop::new(bundle);
+        let added = comp_ids
+            .into_iter()
+            .zip(bundle.component_ptrs().as_ref().iter().copied())
+            .collect::<Vec<_>>();
+        let result = unsafe { self.move_entity_dynamic(entity, &added, &[]) };
+        if result.is_err() {
+            // The components are still owned by us if they couldn't be added
+            unsafe { core::mem::ManuallyDrop::drop(&mut bundle) };
+        }
+        result
+    }
+
+    /// Removes every component in ``B`` from ``entity`` by moving it straight to the final archetype, if the entity
+    /// is missing any of the components nothing is removed
+    ///
+    /// This method will panic if the same component type is in the bundle more than once
+    pub fn try_remove_bundle<B: Bundle>(&mut self, entity: EcsId) -> Result<(), EcsError> {
+        self.flush_reserved();
+        if !self.entities.is_alive(entity) {
+            return Err(EcsError::NoSuchEntity);
+        }
+        let comp_ids = B::type_ids()
+            .iter()
+            // Nothing can have a component of an unregistered type
+            .map(|type_id| self.type_id_to_ecs_id.get(type_id).copied())
+            .collect::<Option<Vec<_>>>()
+            .ok_or(EcsError::Missing)?;
+        unsafe { self.move_entity_dynamic(entity, &[], &comp_ids) }
+    }
+
+    /// Spawns an entity with every component in ``bundle``
+    ///
+    /// This method will panic if the same component type is in the bundle more than once
+    pub fn spawn_bundle<B: Bundle>(&mut self, bundle: B) -> EcsId {
+        let comp_ids = B::comp_ids(self);
+        let mut bundle = core::mem::ManuallyDrop::new(bundle);
+        let mut builder = self.spawn_with_capacity(core::mem::size_of::<B>());
+        for (comp_id, &ptr) in comp_ids.into_iter().zip(bundle.component_ptrs().as_ref()) {
+            // Safe because the pointers come from the bundle which is never used again
+            builder = unsafe { builder.with_dynamic_with_data(ptr, comp_id) };
+        }
+        builder.build()
+    }
+
+    /// Spawns an entity for every bundle in ``bundles``, the archetype is only looked up once and the component storages
+    /// are grown up front using the iterator's size hint. Returns the ids of the spawned entities in the order they were spawned
+    ///
+    /// This method will panic if the same component type is in the bundle more than once
+    pub fn spawn_batch<B: Bundle, I: IntoIterator<Item = B>>(&mut self, bundles: I) -> &[EcsId] {
+        self.flush_reserved();
+        let comp_ids = B::comp_ids(self);
+        let mut sorted_comp_ids = comp_ids.clone();
+        sorted_comp_ids.sort();
+        sorted_comp_ids.dedup();
+        assert!(
+            sorted_comp_ids.len() == comp_ids.len(),
+            "Attempted to add or remove the same component twice in a bundle"
+        );
+
+        let archetype_idx = match self.find_archetype_dynamic(&sorted_comp_ids) {
+            Some(idx) => idx,
+            None => self.create_archetype_dynamic(sorted_comp_ids),
+        };
+
+        let bundles = bundles.into_iter();
+        let archetype = &mut self.archetypes[archetype_idx.0];
+        let storage_idxs = comp_ids
+            .iter()
+            .map(|id| archetype.comp_lookup[id])
+            .collect::<Vec<_>>();
+        let (additional, _) = bundles.size_hint();
+        archetype.entities.reserve(additional);
+        for (_, storage) in archetype.component_storages.iter_mut() {
+            storage.get_mut().reserve(additional);
+        }
+        let start = archetype.entities.len();
+
+        let ticks = ComponentTicks::new(self.change_tick.load(Ordering::Relaxed));
+        for bundle in bundles {
+            let entity = self.entities.spawn();
+            let mut bundle = core::mem::ManuallyDrop::new(bundle);
+            let archetype = &mut self.archetypes[archetype_idx.0];
+            for (&storage_idx, &ptr) in storage_idxs.iter().zip(bundle.component_ptrs().as_ref()) {
+                // Safe because the pointers come from the bundle which is never used again
+                unsafe {
+                    archetype.component_storages[storage_idx]
+                        .1
+                        .get_mut()
+                        .push_raw(ptr as *mut core::mem::MaybeUninit<u8>, ticks);
+                }
+            }
+            archetype.entities.push(entity);
+
+            let entity_meta = EntityMeta {
+                instance_meta: InstanceMeta {
+                    archetype: archetype_idx.clone(),
+                    index: archetype.entities.len() - 1,
+                },
+                component_meta: ComponentMeta::unit(),
+            };
+            self.set_entity_meta(entity, entity_meta);
+        }
+
+        &self.archetypes[archetype_idx.0].entities[start..]
+    }
+
+    pub fn has_component<T: Component>(&self, entity: EcsId) -> bool {
+        let func = || {
+            let comp_id = self.type_id_to_ecs_id.get(&TypeId::of::<T>())?;
+            let ArchIndex(idx) = self.get_entity_meta(entity)?.instance_meta.archetype;
+            Some(self.archetypes[idx].comp_lookup.get(comp_id).is_some())
+        };
+
+        func().unwrap_or(false)
+    }
+
+    /// Gets a reference to the component `T` on `entity`
+    ///
+    /// This takes `&mut self` so that there can't be any queries alive that have locked `T`, which means it
+    /// doesn't need to take any locks itself
+    pub fn get<T: Component>(&mut self, entity: EcsId) -> Option<&T> {
+        let comp_id = *self.type_id_to_ecs_id.get(&TypeId::of::<T>())?;
+        let ptr = self.get_component_ptr(entity, comp_id)?;
+        Some(unsafe { &*(ptr as *const T) })
+    }
+
+    /// Gets a mutable reference to the component `T` on `entity`
+    pub fn get_mut<T: Component>(&mut self, entity: EcsId) -> Option<&mut T> {
+        let comp_id = *self.type_id_to_ecs_id.get(&TypeId::of::<T>())?;
+        let ptr = self.get_component_ptr_mut(entity, comp_id)?;
+        Some(unsafe { &mut *(ptr as *mut T) })
+    }
+
+    /// Gets mutable references to multiple components on `entity` at once, returns None if any are missing
+    ///
+    /// This method will panic if the same component type is given more than once
+    pub fn get_many_mut<G: GetManyMut>(&mut self, entity: EcsId) -> Option<G::Refs<'_>> {
+        G::get_many_mut(self, entity)
+    }
+
+    /// Stores ``resource`` as the component ``T`` of the world's resource entity and returns the resource of the same
+    /// type that it replaced. Resources are ordinary components so queries for ``T`` also match the resource entity,
+    /// but ``World::despawn`` refuses to despawn it
+    pub fn insert_resource<T: Component>(&mut self, resource: T) -> Option<T> {
+        let entity = match self.resource_entity {
+            Some(entity) if self.is_alive(entity) => entity,
+            _ => {
+                let entity = self.spawn().build();
+                self.resource_entity = Some(entity);
+                entity
+            }
+        };
+
+        match self.get_mut::<T>(entity) {
+            Some(old) => Some(core::mem::replace(old, resource)),
+            None => {
+                self.add_component(entity, resource);
+                None
+            }
+        }
+    }
+
+    /// Removes the resource ``T`` from the resource entity and returns it, None if it was never inserted
+    pub fn remove_resource<T: Component>(&mut self) -> Option<T> {
+        self.take(self.resource_entity?)
+    }
+
+    /// The entity that resources are stored on, None if no resource has been inserted yet
+    pub fn resource_entity(&self) -> Option<EcsId> {
+        self.resource_entity
+    }
+
+    /// Borrows the resource ``T``, the component ``T`` stays locked until the returned ``Res`` is dropped
+    ///
+    /// This panics if a query or ``ResMut`` has ``T`` borrowed mutably
+    pub fn resource<T: Component>(&self) -> Option<Res<'_, T>> {
+        let comp_id = *self.type_id_to_ecs_id.get(&TypeId::of::<T>())?;
+        let (ptr, _) = self.resource_ptr(comp_id)?;
+        let guard = self
+            .try_lock(comp_id, false)
+            .unwrap_or_else(|err| panic!("{}", err));
+        Some(Res {
+            _guard: guard,
+            resource: unsafe { &*(ptr as *const T) },
+        })
+    }
+
+    /// Mutably borrows the resource ``T`` and marks it as changed, the component ``T`` stays locked until the
+    /// returned ``ResMut`` is dropped
+    ///
+    /// This panics if a query, ``Res`` or ``ResMut`` has ``T`` borrowed
+    pub fn resource_mut<T: Component>(&self) -> Option<ResMut<'_, T>> {
+        let comp_id = *self.type_id_to_ecs_id.get(&TypeId::of::<T>())?;
+        let (ptr, changed) = self.resource_ptr(comp_id)?;
+        let guard = self
+            .try_lock(comp_id, true)
+            .unwrap_or_else(|err| panic!("{}", err));
+        // Safe because the write lock guarantees nothing else is accessing the resource or its ticks
+        unsafe { *changed = self.change_tick() };
+        Some(ResMut {
+            _guard: guard,
+            resource: unsafe { &mut *(ptr as *mut T) },
+        })
+    }
+
+    /// Pointers to the resource with the id ``comp_id`` and its changed tick, the component has to be locked before
+    /// either pointer is dereferenced
+    pub(crate) fn resource_ptr(&self, comp_id: EcsId) -> Option<(*mut u8, *mut u32)> {
+        let (column, entity_idx) = self.get_column(self.resource_entity?, comp_id)?;
+        let column = column.get();
+        // Safe because only the addresses are taken, neither the data nor the ticks are read
+        unsafe {
+            Some((
+                (*column).data.get_raw(entity_idx).unwrap() as *mut u8,
+                (*column).changed.as_ptr().add(entity_idx) as *mut u32,
+            ))
+        }
+    }
+
+    /// Adds an entity as a dataless component
+    ///
+    /// This method will panic if a component with the ID of component_id expects data. Entities by default expect no data.
+    pub fn add_component_dynamic(&mut self, entity: EcsId, component_id: EcsId) {
+        self.try_add_component_dynamic(entity, component_id)
+            .unwrap();
+    }
+
+    /// Same as ``World::add_component_dynamic`` except returns ``EcsError::NotUnitComponent`` instead of panicking
+    pub fn try_add_component_dynamic(
+        &mut self,
+        entity: EcsId,
+        component_id: EcsId,
+    ) -> Result<(), EcsError> {
+        self.flush_reserved();
+        if !self.entities.is_alive(entity) || !self.is_alive_component(component_id) {
+            return Err(EcsError::NoSuchEntity);
+        }
+        if !self.get_component_meta(component_id).unwrap().is_unit {
+            return Err(EcsError::NotUnitComponent);
+        }
+
+        let dangling = core::ptr::NonNull::dangling().as_ptr();
+        unsafe { self.try_add_component_dynamic_with_data(entity, component_id, dangling) }
+    }
+}
+
+impl World {
+    #[must_use]
+    /// # Safety
+    ///
+    ///    All subsequent uses of this entity as a component must be valid for the given ComponentMeta
+    pub unsafe fn spawn_with_component_meta(
+        &mut self,
+        component_meta: ComponentMeta,
+    ) -> crate::entity_builder::EntityBuilder {
+        self.flush_reserved();
+        let entity = self.entities.spawn();
+
+        crate::entity_builder::EntityBuilder::new(self, entity, component_meta)
+    }
+
+    pub fn get_or_create_type_id_ecsid<T: Component>(&mut self) -> EcsId {
+        let comp_id = self.type_id_to_ecs_id.get(&TypeId::of::<T>());
+        if let Some(comp_id) = comp_id {
+            return *comp_id;
+        }
+
+        self.get_or_create_type_id_ecsid_dynamic(
+            TypeId::of::<T>(),
+            ComponentMeta::from_generic::<T>(),
+        )
+    }
+
+    /// Same as ``World::get_or_create_type_id_ecsid`` but for when the type is only known through its TypeId
+    pub(crate) fn get_or_create_type_id_ecsid_dynamic(
+        &mut self,
+        type_id: TypeId,
+        component_meta: ComponentMeta,
+    ) -> EcsId {
+        if let Some(comp_id) = self.type_id_to_ecs_id.get(&type_id) {
+            return *comp_id;
+        }
+
+        let entity = self.spawn().build();
+
+        // Guaranteed valid because we just spawned the entity
+        let meta = self.ecs_id_meta[entity.uindex()].as_mut().unwrap();
+        meta.component_meta = component_meta;
+
+        self.type_id_to_ecs_id.insert(type_id, entity);
+
+        entity
+    }
+
+    /// Spawns every entity that was reserved since the last flush into the archetype with no components
+    pub(crate) fn flush_reserved(&mut self) {
+        if *self.entities.reserved.get_mut() == 0 {
+            return;
+        }
+
+        for entity in self.entities.flush_reserved() {
+            crate::entity_builder::EntityBuilder::new(self, entity, ComponentMeta::unit()).build();
+        }
+    }
+
+    pub fn get_entity_meta(&self, entity: EcsId) -> Option<&EntityMeta> {
+        if !self.entities.is_alive(entity) {
+            return None;
+        }
+
+        self.ecs_id_meta.get(entity.uindex())?.as_ref()
+    }
+
+    /// The ``ComponentMeta`` of the component ``comp_id``, pairs use the meta of their relation. Returns None if the
+    /// component or either half of the pair is dead
+    pub fn get_component_meta(&self, comp_id: EcsId) -> Option<&ComponentMeta> {
+        if comp_id.is_pair() {
+            self.entities.alive_at(comp_id.uindex())?;
+            let relation = self.entities.alive_at(comp_id.relation_index())?;
+            return Some(&self.get_entity_meta(relation)?.component_meta);
+        }
+        Some(&self.get_entity_meta(comp_id)?.component_meta)
+    }
+
+    /// Returns true if ``comp_id`` is an entity that is alive or a pair whose relation and target are both alive
+    pub(crate) fn is_alive_component(&self, comp_id: EcsId) -> bool {
+        match comp_id.is_pair() {
+            true => {
+                self.entities.alive_at(comp_id.relation_index()).is_some()
+                    && self.entities.alive_at(comp_id.uindex()).is_some()
+            }
+            false => self.entities.is_alive(comp_id),
+        }
+    }
+
+    pub(crate) fn set_entity_meta(&mut self, entity: EcsId, meta: EntityMeta) {
+        if self.entities.is_alive(entity) {
+            let new_meta = Some(meta);
+            match self.ecs_id_meta.get_mut(entity.uindex()) {
+                Some(old_meta) => *old_meta = new_meta,
+                None => {
+                    self.ecs_id_meta.resize_with(entity.uindex(), || None);
+                    self.ecs_id_meta.push(new_meta);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn query_archetypes<'a, const N: usize>(
+        &'a self,
+        iters: [(Iter<'a, usize>, fn(usize) -> usize); N],
+        bit_length: u32,
+    ) -> ArchetypeIter<'a, N> {
+        ArchetypeIter {
+            archetypes: &self.archetypes,
+            iter: BitsetIterator::new(iters, bit_length),
+        }
+    }
+
+    /// Finds the archetype with exactly the components in ``comp_ids``, which do not need to be sorted
+    pub(crate) fn find_archetype_dynamic(&self, comp_ids: &[EcsId]) -> Option<ArchIndex> {
+        if comp_ids.windows(2).all(|ids| ids[0] <= ids[1]) {
+            return self.archetype_lookup.get(comp_ids).cloned();
+        }
+
+        let mut sorted_comp_ids = comp_ids.to_vec();
+        sorted_comp_ids.sort();
+        self.archetype_lookup.get(&sorted_comp_ids).cloned()
+    }
+
+    /// Finds the archetype with the components in the sorted ``comp_ids`` plus ``extra_id``
+    pub(crate) fn find_archetype_dynamic_plus_id(
+        &self,
+        comp_ids: &[EcsId],
+        extra_id: EcsId,
+    ) -> Option<usize> {
+        let mut target_comp_ids = Vec::with_capacity(comp_ids.len() + 1);
+        target_comp_ids.extend_from_slice(comp_ids);
+        let insert_idx = target_comp_ids.binary_search(&extra_id).unwrap_err();
+        target_comp_ids.insert(insert_idx, extra_id);
+
+        self.archetype_lookup
+            .get(&target_comp_ids)
+            .map(|ArchIndex(idx)| *idx)
+    }
+
+    /// Finds the archetype with the components in the sorted ``comp_ids`` minus ``without_id``
+    pub(crate) fn find_archetype_dynamic_minus_id(
+        &self,
+        comp_ids: &[EcsId],
+        without_id: EcsId,
+    ) -> Option<usize> {
+        let target_comp_ids = comp_ids
+            .iter()
+            .filter(|&&id| id != without_id)
+            .copied()
+            .collect::<Vec<_>>();
+
+        self.archetype_lookup
+            .get(&target_comp_ids)
+            .map(|ArchIndex(idx)| *idx)
+    }
+
+    /// Does nothing if either ``entity`` or ``comp_id`` are not alive, panics if the component is already present
+    ///
+    /// # Safety
+    ///
+    ///   ``component_ptr`` must point to data that matches the component_meta of component_id.
+    ///   The data must also not be used after calling this function.
     pub unsafe fn add_component_dynamic_with_data(
         &mut self,
         entity: EcsId,
         comp_id: EcsId,
         component_ptr: *mut u8,
     ) {
-        if !self.entities.is_alive(entity) {
-            return;
+        match unsafe { self.try_add_component_dynamic_with_data(entity, comp_id, component_ptr) } {
+            Ok(()) | Err(EcsError::NoSuchEntity) => (),
+            Err(err) => panic!("{}", err),
         }
-        if !self.entities.is_alive(comp_id) {
-            return;
+    }
+
+    /// Same as ``World::insert`` but for any component id
+    ///
+    /// # Safety
+    ///
+    ///   ``component_ptr`` must point to data that matches the component_meta of component_id.
+    ///   If this returns Ok the data must not be used again, if it returns an error the data is still owned by the caller
+    pub unsafe fn insert_dynamic_with_data(
+        &mut self,
+        entity: EcsId,
+        comp_id: EcsId,
+        component_ptr: *mut u8,
+    ) -> Result<(), EcsError> {
+        self.flush_reserved();
+        if !self.entities.is_alive(entity) || !self.is_alive_component(comp_id) {
+            return Err(EcsError::NoSuchEntity);
+        }
+
+        match self.get_component_ptr_mut(entity, comp_id) {
+            Some(existing) => {
+                let meta = self.get_component_meta(comp_id).unwrap();
+                unsafe {
+                    if let Some(drop_fn) = meta.drop_fn {
+                        drop_fn(existing as *mut _);
+                    }
+                    std::ptr::copy_nonoverlapping(component_ptr, existing, meta.layout.size());
+                }
+                Ok(())
+            }
+            None => unsafe {
+                self.try_add_component_dynamic_with_data(entity, comp_id, component_ptr)
+            },
+        }
+    }
+
+    /// # Safety
+    ///
+    ///   ``component_ptr`` must point to data that matches the component_meta of component_id.
+    ///   If this returns Ok the data must not be used again, if it returns an error the data is still owned by the caller
+    pub unsafe fn try_add_component_dynamic_with_data(
+        &mut self,
+        entity: EcsId,
+        comp_id: EcsId,
+        component_ptr: *mut u8,
+    ) -> Result<(), EcsError> {
+        self.flush_reserved();
+        if !self.entities.is_alive(entity) || !self.is_alive_component(comp_id) {
+            return Err(EcsError::NoSuchEntity);
         }
 
         let (current_archetype_idx, entity_idx) = {
@@ -603,64 +1558,23 @@ impl World {
         };
         let current_archetype = &mut self.archetypes[current_archetype_idx.0];
         // Note, this is important, caching will give us *wrong* results if we try and add a component that is in this archetype
-        assert!(!current_archetype.comp_ids.contains(&comp_id));
+        if current_archetype.comp_lookup.contains_key(&comp_id) {
+            return Err(EcsError::AlreadyPresent);
+        }
 
         let target_archetype_idx = current_archetype
-            .try_find_next_archetype(comp_id)
-            .or_else(|| {
-                // Iterate every archeype to see if one exists
-                // TODO MAYBE: technically we dont need to iterate everything, we can calculate the exact archetype.type_ids the
-                // target archetype will have so we could store a hashmap of that -> archetype_idx in world to avoid this O(n) lookup
-
-                let current_archetype = &self.archetypes[current_archetype_idx.0];
-                let idx = self.find_archetype_dynamic_plus_id(&current_archetype.comp_ids, comp_id);
-
-                if let Some(idx) = idx {
-                    let current_archetype = &mut self.archetypes[current_archetype_idx.0];
-                    current_archetype.insert_archetype_cache(comp_id, idx);
-                }
-
-                idx
-            })
+            .add_edges
+            .lookup_id(comp_id)
             .map(ArchIndex)
             .unwrap_or_else(|| {
-                // Create a new archetype
-                use std::collections::hash_map::Entry;
-                let entry = self.lock_lookup.entry(comp_id);
-                if let Entry::Vacant(entry) = entry {
-                    entry.insert(self.locks.len());
-                    self.locks.push(RwLock::new(()));
-                }
-
-                let (layout, drop_fn) = {
-                    let meta = self
-                        .get_entity_meta(comp_id)
-                        .unwrap()
-                        .component_meta
-                        .clone();
-                    (meta.layout, meta.drop_fn)
-                };
-
-                let archetype = unsafe {
-                    Archetype::from_archetype_with(
-                        &mut self.archetypes[current_archetype_idx.0],
-                        untyped_vec::TypeInfo::new(layout, drop_fn),
-                        comp_id,
-                    )
-                };
-
-                for id in archetype.comp_ids.iter() {
-                    self.archetype_bitset
-                        .set_bit(*id, self.archetypes.len(), true);
-                }
-                self.entities_bitvec.push_bit(true);
-
-                self.archetypes.push(archetype);
+                let current_archetype = &self.archetypes[current_archetype_idx.0];
+                let archetype_idx = self
+                    .find_archetype_dynamic_plus_id(&current_archetype.comp_ids, comp_id)
+                    .map(ArchIndex)
+                    .unwrap_or_else(|| self.create_archetype_with(&current_archetype_idx, comp_id));
 
-                let archetypes_len = self.archetypes.len();
-                let current_archetype = &mut self.archetypes[current_archetype_idx.0];
-                current_archetype.insert_archetype_cache(comp_id, archetypes_len - 1);
-                ArchIndex(archetypes_len - 1)
+                self.link_archetypes(&current_archetype_idx, &archetype_idx, comp_id);
+                archetype_idx
             });
 
         let (current_archetype, target_archetype) = crate::utils::index_twice_mut(
@@ -701,11 +1615,12 @@ impl World {
             skipped_idx = Some(target_archetype.component_storages.len() - 1);
         }
 
+        let ticks = ComponentTicks::new(self.change_tick.load(Ordering::Relaxed));
         unsafe {
             target_archetype.component_storages[skipped_idx.unwrap()]
                 .1
                 .get_mut()
-                .push_raw(component_ptr as *mut core::mem::MaybeUninit<u8>);
+                .push_raw(component_ptr as *mut core::mem::MaybeUninit<u8>, ticks);
         }
 
         target_archetype.entities.push(entity);
@@ -725,14 +1640,68 @@ impl World {
                 .instance_meta
                 .index = entity_idx;
         }
+        Ok(())
     }
 
+    /// Does nothing if either ``entity`` or ``comp_id`` are not alive, panics if the component is missing
     pub fn remove_component_dynamic(&mut self, entity: EcsId, comp_id: EcsId) {
-        if !self.entities.is_alive(entity) {
-            return;
+        match self.try_remove_component_dynamic(entity, comp_id) {
+            Ok(()) | Err(EcsError::NoSuchEntity) => (),
+            Err(err) => panic!("{}", err),
         }
-        if !self.entities.is_alive(comp_id) {
-            return;
+    }
+
+    pub fn try_remove_component_dynamic(
+        &mut self,
+        entity: EcsId,
+        comp_id: EcsId,
+    ) -> Result<(), EcsError> {
+        // Safe because passing no destination drops the component
+        unsafe { self.remove_component_into(entity, comp_id, None) }
+    }
+
+    /// Removes the component ``T`` from ``entity`` and returns it
+    pub fn take<T: Component>(&mut self, entity: EcsId) -> Option<T> {
+        let comp_id = *self.type_id_to_ecs_id.get(&TypeId::of::<T>())?;
+        let mut component = core::mem::MaybeUninit::<T>::uninit();
+        // Safe because comp_id is the id for T so the destination is valid for writes of the component
+        unsafe {
+            self.remove_component_into(entity, comp_id, Some(component.as_mut_ptr() as *mut u8))
+                .ok()?;
+            Some(component.assume_init())
+        }
+    }
+
+    /// Removes the component from ``entity`` and moves it into ``dst`` instead of dropping it
+    ///
+    /// # Safety
+    ///
+    ///   ``dst`` must be valid for writes of ``component_meta.layout.size()`` bytes where ``component_meta`` is the
+    ///   ComponentMeta of comp_id, it does not need to be aligned. If this returns Ok the caller is responsible for
+    ///   dropping the component.
+    pub unsafe fn take_dynamic(
+        &mut self,
+        entity: EcsId,
+        comp_id: EcsId,
+        dst: *mut u8,
+    ) -> Result<(), EcsError> {
+        unsafe { self.remove_component_into(entity, comp_id, Some(dst)) }
+    }
+
+    /// Removes ``comp_id`` from ``entity``, the component is moved into ``dst`` if it is given otherwise it is dropped
+    ///
+    /// # Safety
+    ///
+    ///   See ``World::take_dynamic``
+    unsafe fn remove_component_into(
+        &mut self,
+        entity: EcsId,
+        comp_id: EcsId,
+        dst: Option<*mut u8>,
+    ) -> Result<(), EcsError> {
+        self.flush_reserved();
+        if !self.entities.is_alive(entity) || !self.is_alive_component(comp_id) {
+            return Err(EcsError::NoSuchEntity);
         }
 
         let (current_archetype_idx, entity_idx) = {
@@ -742,49 +1711,14 @@ impl World {
                 meta.instance_meta.index,
             )
         };
-        let current_archetype = &mut self.archetypes[current_archetype_idx.0];
-        // Note, this is important, caching will give us *wrong* results if we try and remove a component that isnt in this archetype
-        assert!(current_archetype.comp_ids.contains(&comp_id));
-
-        let target_archetype_idx = current_archetype
-            .try_find_next_archetype(comp_id)
-            .or_else(|| {
-                // Iterate every archeype to see if one exists
-                // TODO MAYBE: technically we dont need to iterate everything, we can calculate the exact archetype.type_ids the
-                // target archetype will have so we could store a hashmap of that -> archetype_idx in world to avoid this O(n) lookup
-
-                let current_archetype = &self.archetypes[current_archetype_idx.0];
-                let idx =
-                    self.find_archetype_dynamic_minus_id(&current_archetype.comp_ids, comp_id);
-
-                if let Some(idx) = idx {
-                    let current_archetype = &mut self.archetypes[current_archetype_idx.0];
-                    current_archetype.insert_archetype_cache(comp_id, idx);
-                }
-
-                idx
-            })
-            .map(ArchIndex)
-            .unwrap_or_else(|| {
-                // Create a new archetype
-                let archetype = Archetype::from_archetype_without(
-                    &mut self.archetypes[current_archetype_idx.0],
-                    comp_id,
-                );
-
-                for id in archetype.comp_ids.iter() {
-                    self.archetype_bitset
-                        .set_bit(*id, self.archetypes.len(), true);
-                }
-                self.entities_bitvec.push_bit(true);
-
-                self.archetypes.push(archetype);
-
-                let archetypes_len = self.archetypes.len();
-                let current_archetype = &mut self.archetypes[current_archetype_idx.0];
-                current_archetype.insert_archetype_cache(comp_id, archetypes_len - 1);
-                ArchIndex(archetypes_len - 1)
-            });
+        if !self.archetypes[current_archetype_idx.0]
+            .comp_lookup
+            .contains_key(&comp_id)
+        {
+            return Err(EcsError::Missing);
+        }
+        let target_archetype_idx =
+            self.find_or_create_archetype_without(&current_archetype_idx, comp_id);
 
         let (current_archetype, target_archetype) = crate::utils::index_twice_mut(
             current_archetype_idx.0,
@@ -822,10 +1756,148 @@ impl World {
             skipped_storage = Some(current_archetype.component_storages.len() - 1);
         }
 
-        current_archetype.component_storages[skipped_storage.unwrap()]
+        let removed_storage = current_archetype.component_storages[skipped_storage.unwrap()]
             .1
-            .get_mut()
-            .swap_remove(entity_idx);
+            .get_mut();
+        match dst {
+            Some(dst) => unsafe { removed_storage.swap_remove_into(entity_idx, dst as *mut _) },
+            None => removed_storage.swap_remove(entity_idx),
+        }
+        self.removed_components
+            .entry(comp_id)
+            .or_default()
+            .push(entity);
+
+        target_archetype.entities.push(entity);
+        self.ecs_id_meta[entity.uindex()]
+            .as_mut()
+            .unwrap()
+            .instance_meta = InstanceMeta {
+            archetype: target_archetype_idx,
+            index: target_archetype.entities.len() - 1,
+        };
+
+        current_archetype.entities.swap_remove(entity_idx);
+        if let Some(&swapped_entity) = current_archetype.entities.get(entity_idx) {
+            self.ecs_id_meta[swapped_entity.uindex()]
+                .as_mut()
+                .unwrap()
+                .instance_meta
+                .index = entity_idx;
+        }
+        Ok(())
+    }
+
+    /// Adds every component in ``added`` and removes every component in ``removed`` from ``entity`` with a single move
+    /// to the final archetype, the removed components are dropped. If any component can't be added or removed
+    /// the entity is left untouched
+    ///
+    /// # Safety
+    ///
+    ///   Every pointer in ``added`` must point to data that matches the component_meta of its id.
+    ///   If this returns Ok the data must not be used again, if it returns an error the data is still owned by the caller
+    unsafe fn move_entity_dynamic(
+        &mut self,
+        entity: EcsId,
+        added: &[(EcsId, *mut u8)],
+        removed: &[EcsId],
+    ) -> Result<(), EcsError> {
+        let mut added_ids = added.iter().map(|&(id, _)| id).collect::<Vec<_>>();
+        let mut removed_ids = removed.to_vec();
+        for ids in [&mut added_ids, &mut removed_ids] {
+            let len = ids.len();
+            ids.sort();
+            ids.dedup();
+            assert!(
+                ids.len() == len,
+                "Attempted to add or remove the same component twice in a bundle"
+            );
+        }
+
+        self.flush_reserved();
+        let all_alive = self.entities.is_alive(entity)
+            && added
+                .iter()
+                .map(|&(id, _)| id)
+                .chain(removed.iter().copied())
+                .all(|id| self.is_alive_component(id));
+        if !all_alive {
+            return Err(EcsError::NoSuchEntity);
+        }
+
+        let (current_archetype_idx, entity_idx) = {
+            let meta = self.get_entity_meta(entity).unwrap();
+            (
+                meta.instance_meta.archetype.clone(),
+                meta.instance_meta.index,
+            )
+        };
+        let current_archetype = &self.archetypes[current_archetype_idx.0];
+        if added
+            .iter()
+            .any(|(id, _)| current_archetype.comp_lookup.contains_key(id))
+        {
+            return Err(EcsError::AlreadyPresent);
+        }
+        if !removed
+            .iter()
+            .all(|id| current_archetype.comp_lookup.contains_key(id))
+        {
+            return Err(EcsError::Missing);
+        }
+
+        let mut target_comp_ids = current_archetype
+            .comp_ids
+            .iter()
+            .filter(|id| !removed.contains(id))
+            .copied()
+            .chain(added_ids)
+            .collect::<Vec<_>>();
+        target_comp_ids.sort();
+
+        if added.is_empty() && removed.is_empty() {
+            return Ok(());
+        }
+
+        let target_archetype_idx = match self.find_archetype_dynamic(&target_comp_ids) {
+            Some(idx) => idx,
+            None => self.create_archetype_dynamic(target_comp_ids),
+        };
+
+        let (current_archetype, target_archetype) = crate::utils::index_twice_mut(
+            current_archetype_idx.0,
+            target_archetype_idx.0,
+            &mut self.archetypes,
+        );
+
+        for (comp_id, storage) in current_archetype.component_storages.iter_mut() {
+            let storage = storage.get_mut();
+            match target_archetype.comp_lookup.get(comp_id) {
+                Some(&target_idx) => unsafe {
+                    let target_storage =
+                        target_archetype.component_storages[target_idx].1.get_mut();
+                    storage.swap_move_element_to_other_vec(target_storage, entity_idx)
+                },
+                None => {
+                    storage.swap_remove(entity_idx);
+                    self.removed_components
+                        .entry(*comp_id)
+                        .or_default()
+                        .push(entity);
+                }
+            }
+        }
+
+        let ticks = ComponentTicks::new(self.change_tick.load(Ordering::Relaxed));
+        for &(comp_id, component_ptr) in added {
+            let target_idx = target_archetype.comp_lookup[&comp_id];
+            unsafe {
+                target_archetype.component_storages[target_idx]
+                    .1
+                    .get_mut()
+                    .push_raw(component_ptr as *mut core::mem::MaybeUninit<u8>, ticks);
+            }
+        }
 
         target_archetype.entities.push(entity);
         self.ecs_id_meta[entity.uindex()]
@@ -844,13 +1916,209 @@ impl World {
                 .instance_meta
                 .index = entity_idx;
         }
+        Ok(())
+    }
+
+    /// Creates an empty archetype with the components in ``comp_ids`` which must be sorted and not already have an archetype
+    pub(crate) fn create_archetype_dynamic(&mut self, comp_ids: Vec<EcsId>) -> ArchIndex {
+        let mut component_storages = Vec::with_capacity(comp_ids.len());
+        let mut comp_lookup =
+            HashMap::with_capacity_and_hasher(comp_ids.len(), crate::utils::TypeIdHasherBuilder());
+        for (n, &id) in comp_ids.iter().enumerate() {
+            use std::collections::hash_map::Entry;
+            if let Entry::Vacant(entry) = self.lock_lookup.entry(id) {
+                entry.insert(self.locks.len());
+                self.locks.push(RwLock::new(()));
+            }
+
+            let meta = self.get_component_meta(id).unwrap();
+            let column = unsafe { Column::new(TypeInfo::new(meta.layout, meta.drop_fn)) };
+            component_storages.push((id, UnsafeCell::new(column)));
+            comp_lookup.insert(id, n);
+        }
+
+        self.push_archetype(Archetype {
+            comp_lookup,
+            entities: Vec::new(),
+            component_storages,
+            comp_ids,
+            add_edges: ArchetypeEdges::new(),
+            remove_edges: ArchetypeEdges::new(),
+        })
+    }
+
+    /// Adds a newly created archetype to the world and registers it with the archetype bitsets and ``World::archetype_lookup``
+    pub(crate) fn push_archetype(&mut self, archetype: Archetype) -> ArchIndex {
+        let archetype_idx = ArchIndex(self.archetypes.len());
+        self.archetype_bitset
+            .set_archetype_bits(&archetype.comp_ids, archetype_idx.0, true);
+        self.entities_bitvec.push_bit(true);
+
+        let previous = self
+            .archetype_lookup
+            .insert(archetype.comp_ids.clone(), archetype_idx.clone());
+        assert!(previous.is_none());
+        self.archetypes.push(archetype);
+        archetype_idx
+    }
+
+    /// Finds the archetype with the same components as ``current_archetype_idx`` minus ``comp_id``, creating it if it doesn't exist
+    fn find_or_create_archetype_without(
+        &mut self,
+        current_archetype_idx: &ArchIndex,
+        comp_id: EcsId,
+    ) -> ArchIndex {
+        let current_archetype = &mut self.archetypes[current_archetype_idx.0];
+        // Note, this is important, caching will give us *wrong* results if we try and remove a component that isnt in this archetype
+        assert!(current_archetype.comp_ids.contains(&comp_id));
+
+        current_archetype
+            .remove_edges
+            .lookup_id(comp_id)
+            .map(ArchIndex)
+            .unwrap_or_else(|| {
+                let current_archetype = &self.archetypes[current_archetype_idx.0];
+                let archetype_idx = self
+                    .find_archetype_dynamic_minus_id(&current_archetype.comp_ids, comp_id)
+                    .map(ArchIndex)
+                    .unwrap_or_else(|| {
+                        let archetype = Archetype::from_archetype_without(
+                            &mut self.archetypes[current_archetype_idx.0],
+                            comp_id,
+                        );
+                        self.push_archetype(archetype)
+                    });
+
+                self.link_archetypes(&archetype_idx, current_archetype_idx, comp_id);
+                archetype_idx
+            })
     }
 
+    /// Creates the archetype with the same components as ``current_archetype_idx`` plus ``comp_id``
+    fn create_archetype_with(
+        &mut self,
+        current_archetype_idx: &ArchIndex,
+        comp_id: EcsId,
+    ) -> ArchIndex {
+        use std::collections::hash_map::Entry;
+        let entry = self.lock_lookup.entry(comp_id);
+        if let Entry::Vacant(entry) = entry {
+            entry.insert(self.locks.len());
+            self.locks.push(RwLock::new(()));
+        }
+
+        let (layout, drop_fn) = {
+            let meta = self.get_component_meta(comp_id).unwrap().clone();
+            (meta.layout, meta.drop_fn)
+        };
+
+        let archetype = unsafe {
+            Archetype::from_archetype_with(
+                &mut self.archetypes[current_archetype_idx.0],
+                TypeInfo::new(layout, drop_fn),
+                comp_id,
+            )
+        };
+
+        self.push_archetype(archetype)
+    }
+
+    /// Adds the edges between two archetypes in both directions, ``with_idx`` must have the same components as
+    /// ``without_idx`` plus ``comp_id``
+    fn link_archetypes(&mut self, without_idx: &ArchIndex, with_idx: &ArchIndex, comp_id: EcsId) {
+        self.archetypes[without_idx.0]
+            .add_edges
+            .insert_id(comp_id, with_idx.0);
+        self.archetypes[with_idx.0]
+            .remove_edges
+            .insert_id(comp_id, without_idx.0);
+    }
+
+    /// Moves every entity in ``archetype_idx`` into the archetype without ``comp_id`` and then empties out ``archetype_idx``,
+    /// the emptied archetype is kept in ``World::archetypes`` so that archetype indices stay valid but will never be found again
+    fn remove_archetype_with_dead_component(&mut self, archetype_idx: ArchIndex, comp_id: EcsId) {
+        let target_archetype_idx = self.find_or_create_archetype_without(&archetype_idx, comp_id);
+
+        let (dead_archetype, target_archetype) = crate::utils::index_twice_mut(
+            archetype_idx.0,
+            target_archetype_idx.0,
+            &mut self.archetypes,
+        );
+
+        Iterator::zip(
+            dead_archetype
+                .component_storages
+                .iter_mut()
+                .filter(|(id, _)| *id != comp_id)
+                .map(|(_, storage)| storage.get_mut()),
+            target_archetype
+                .component_storages
+                .iter_mut()
+                .map(|(_, storage)| storage.get_mut()),
+        )
+        .for_each(|(dead_storage, tar_storage)| unsafe {
+            // Safe because component_storages in archetypes are sorted and we skip the component_storage that isn't the same
+            dead_storage.move_all_to_other_vec(tar_storage)
+        });
+
+        for entity in dead_archetype.entities.drain(..) {
+            target_archetype.entities.push(entity);
+            self.ecs_id_meta[entity.uindex()]
+                .as_mut()
+                .unwrap()
+                .instance_meta = InstanceMeta {
+                archetype: target_archetype_idx.clone(),
+                index: target_archetype.entities.len() - 1,
+            };
+        }
+
+        self.archetype_bitset
+            .set_archetype_bits(&dead_archetype.comp_ids, archetype_idx.0, false);
+        self.archetype_lookup.remove(&dead_archetype.comp_ids);
+        self.entities_bitvec.set_bit(archetype_idx.0, false);
+
+        // Dropping the storages drops every instance of the dead component
+        dead_archetype.component_storages.clear();
+        dead_archetype.comp_ids.clear();
+        dead_archetype.comp_lookup.clear();
+        dead_archetype.add_edges = ArchetypeEdges::new();
+        dead_archetype.remove_edges = ArchetypeEdges::new();
+    }
+
+    /// Marks the component as changed, see ``World::change_tick``
     pub fn get_component_mut_dynamic(&mut self, entity: EcsId, comp_id: EcsId) -> Option<*mut u8> {
+        self.get_component_ptr_mut(entity, comp_id)
+    }
+
+    pub fn get_component_dynamic(&self, entity: EcsId, comp_id: EcsId) -> Option<*const u8> {
+        self.get_component_ptr(entity, comp_id)
+            .map(|ptr| ptr as *const u8)
+    }
+
+    /// Looks up the component through ``ecs_id_meta`` without taking any locks, callers must make sure that creating
+    /// a reference from the returned pointer can't alias with any outstanding queries
+    fn get_component_ptr(&self, entity: EcsId, comp_id: EcsId) -> Option<*mut u8> {
+        let (column, entity_idx) = self.get_column(entity, comp_id)?;
+        // Safe because we only create a shared reference to the Column for the duration of this call
+        let column = unsafe { &*column.get() };
+        Some(column.data.get_raw(entity_idx).unwrap() as *mut u8)
+    }
+
+    /// Same as ``World::get_component_ptr`` but also marks the component as changed
+    fn get_component_ptr_mut(&mut self, entity: EcsId, comp_id: EcsId) -> Option<*mut u8> {
+        let change_tick = self.change_tick();
+        let (column, entity_idx) = self.get_column(entity, comp_id)?;
+        // Safe because we have mutable access to the world so nothing else can be accessing the Column
+        let column = unsafe { &mut *column.get() };
+        column.changed[entity_idx] = change_tick;
+        Some(column.data.get_mut_raw(entity_idx).unwrap())
+    }
+
+    fn get_column(&self, entity: EcsId, comp_id: EcsId) -> Option<(&UnsafeCell<Column>, usize)> {
         if !self.entities.is_alive(entity) {
             return None;
         }
-        if !self.entities.is_alive(comp_id) {
+        if !self.is_alive_component(comp_id) {
             return None;
         }
 
@@ -861,16 +2129,70 @@ impl World {
                 meta.instance_meta.index,
             )
         };
-        let archetype = &mut self.archetypes[archetype_idx.0];
-
-        let component_storage_idx = archetype.comp_lookup[&comp_id];
+        let archetype = &self.archetypes[archetype_idx.0];
 
-        Some(
-            archetype.component_storages[component_storage_idx]
-                .1
-                .get_mut()
-                .get_mut_raw(entity_idx)
-                .unwrap(),
-        )
+        let &component_storage_idx = archetype.comp_lookup.get(&comp_id)?;
+        Some((
+            &archetype.component_storages[component_storage_idx].1,
+            entity_idx,
+        ))
     }
 }
+
+pub trait GetManyMut: 'static {
+    type Refs<'a>;
+
+    fn get_many_mut(world: &mut World, entity: EcsId) -> Option<Self::Refs<'_>>;
+}
+
+macro_rules! impl_get_many_mut {
+    ($($T:ident)*) => {
+        impl<$($T: Component),*> GetManyMut for ($($T,)*) {
+            type Refs<'a> = ($(&'a mut $T,)*);
+
+            #[allow(non_snake_case)]
+            fn get_many_mut(world: &mut World, entity: EcsId) -> Option<Self::Refs<'_>> {
+                let type_ids = [$(TypeId::of::<$T>(),)*];
+                for (n, type_id) in type_ids.iter().enumerate() {
+                    assert!(
+                        !type_ids[n + 1..].contains(type_id),
+                        "Attempted to get the same component mutably more than once in World::get_many_mut"
+                    );
+                }
+
+                $(
+                    let $T = {
+                        let comp_id = *world.type_id_to_ecs_id.get(&TypeId::of::<$T>())?;
+                        let (column, entity_idx) = world.get_column(entity, comp_id)?;
+                        (column.get(), entity_idx)
+                    };
+                )*
+                // Nothing is marked as changed unless every component was found
+                let change_tick = world.change_tick();
+                $(
+                    let $T = {
+                        let (column, entity_idx) = $T;
+                        // Safe because we have mutable access to the world so nothing else can be accessing the Column
+                        let column = unsafe { &mut *column };
+                        column.changed[entity_idx] = change_tick;
+                        column.data.get_mut_raw(entity_idx).unwrap()
+                    };
+                )*
+                // Safe because every component type is asserted to be unique so none of the references alias,
+                // and `world` is borrowed mutably so there can't be any queries holding locks on the components
+                unsafe { Some(($(&mut *($T as *mut $T),)*)) }
+            }
+        }
+    };
+}
+
+impl_get_many_mut!(A B C D E F G H J K);
+impl_get_many_mut!(A B C D E F G H J);
+impl_get_many_mut!(A B C D E F G H);
+impl_get_many_mut!(A B C D E F G);
+impl_get_many_mut!(A B C D E F);
+impl_get_many_mut!(A B C D E);
+impl_get_many_mut!(A B C D);
+impl_get_many_mut!(A B C);
+impl_get_many_mut!(A B);
+impl_get_many_mut!(A);
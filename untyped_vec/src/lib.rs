@@ -85,9 +85,53 @@ impl UntypedVec {
             panic!("Attempted to reallocate an UntypedVec who's data is size 0");
         }
 
-        if self.cap == 0 {
-            let new_cap = self.type_info.layout.size() * 4;
+        let new_cap = match self.cap {
+            0 => self.type_info.layout.size() * 4,
+            cap => cap * 2,
+        };
+        self.realloc_to(new_cap);
+    }
+
+    /// Reserves capacity for at least ``additional`` more elements, does nothing for zero sized types
+    pub fn reserve(&mut self, additional: usize) {
+        let size = self.type_info.layout.size();
+        if size == 0 {
+            return;
+        }
+
+        let required = additional
+            .checked_mul(size)
+            .and_then(|additional| additional.checked_add(self.len))
+            .expect("Cannot allocate more than isize::MAX bytes");
+        if required > self.cap {
+            self.realloc_to(usize::max(required, self.cap * 2));
+        }
+    }
 
+    /// Shrinks the allocation to fit the current length, deallocating it entirely if the vec is empty
+    pub fn shrink_to_fit(&mut self) {
+        // Zero sized types never allocate and their len is in elements rather than bytes
+        if self.type_info.layout.size() == 0 || self.cap == self.len {
+            return;
+        }
+
+        if self.len == 0 {
+            let layout = Layout::from_size_align(self.cap, self.type_info.layout.align()).unwrap();
+            // Safe because cap is non-zero so the memory was allocated by us with this layout
+            unsafe { dealloc(self.data.as_ptr(), layout) };
+            self.data = self.type_info.dangling();
+            self.cap = 0;
+            return;
+        }
+
+        self.realloc_to(self.len);
+    }
+
+    /// Reallocates to a capacity of ``new_cap`` bytes, ``new_cap`` must be non-zero and a multiple of the type's size
+    fn realloc_to(&mut self, new_cap: usize) {
+        assert!(new_cap < isize::MAX as usize);
+
+        if self.cap == 0 {
             let layout = Layout::from_size_align(new_cap, self.type_info.layout.align()).unwrap();
             // Safe because type info size is always non-zero and thus new_cap is always non-zero
             let ptr = unsafe { alloc(layout) };
@@ -95,8 +139,6 @@ impl UntypedVec {
 
             self.cap = new_cap;
         } else {
-            let new_cap = self.cap * 2;
-            assert!(new_cap < isize::MAX as usize);
             let old_layout =
                 Layout::from_size_align(self.cap, self.type_info.layout.align()).unwrap();
 
@@ -275,6 +317,40 @@ impl UntypedVec {
         }
     }
 
+    /// Moves every element of this vec onto the end of ``other``, preserving their order and leaving this vec empty
+    ///
+    /// # Safety
+    ///
+    ///  The other UntypedVec must be of the same type
+    pub unsafe fn move_all_to_other_vec(&mut self, other: &mut UntypedVec) {
+        assert!(self.type_info == other.type_info);
+
+        if self.type_info.layout.size() == 0 {
+            other.len += self.len;
+            self.len = 0;
+            return;
+        }
+
+        if self.len == 0 {
+            return;
+        }
+
+        while other.len + self.len > other.cap {
+            other.realloc();
+        }
+
+        unsafe {
+            // Safe because we're offsetting inside the allocation and len is never >= isize::MAX
+            let dst = other.data.as_ptr().add(other.len);
+            // The pointers are guaranteed to be nonoverlapping as we are writing to uninitialised memory in the other vec
+            std::ptr::copy_nonoverlapping(self.data.as_ptr(), dst, self.len);
+        }
+
+        other.len += self.len;
+        // Safe because setting the length to zero is effectively mem::forget for the moved elements
+        self.len = 0;
+    }
+
     pub fn swap_remove(&mut self, element: usize) {
         assert!(self.len > 0);
 
@@ -306,6 +382,39 @@ impl UntypedVec {
         }
     }
 
+    /// Same as ``UntypedVec::swap_remove`` except the removed element is moved into ``dst`` instead of being dropped
+    ///
+    /// # Safety
+    ///
+    ///  ``dst`` must be valid for writes of ``type_info.layout.size()`` bytes, it does not need to be aligned
+    pub unsafe fn swap_remove_into(&mut self, element: usize, dst: *mut MaybeUninit<u8>) {
+        assert!(self.len > 0);
+
+        let size = self.type_info.layout.size();
+        assert!(size == 0 || element < self.len / size);
+
+        if size == 0 {
+            self.len -= 1;
+            return;
+        }
+
+        let data = self.data.as_ptr() as *mut MaybeUninit<u8>;
+        unsafe {
+            // Safe because we're offsetting inside the allocation and len is never >= isize::MAX
+            let to_move = data.add(element * size);
+            let last = data.add(self.len - size);
+
+            std::ptr::copy_nonoverlapping(to_move, dst, size);
+            if to_move != last {
+                // Safe because the element at to_move was just moved out so it can be overwritten
+                std::ptr::copy_nonoverlapping(last, to_move, size);
+            }
+        }
+
+        // Safe because the last element was either moved out or moved into the removed element's place
+        self.len -= size;
+    }
+
     /// # Safety
     ///
     ///   The generic used must be the same as the type used for push_raw and must correspond to the data for the EcsId in TypeInfo
@@ -421,6 +530,48 @@ mod untyped_vec {
         assert!(untyped_vec.type_info.layout == Layout::new::<u32>());
     }
 
+    #[test]
+    pub fn reserve() {
+        let mut untyped_vec = untyped_vec_new::<u32>();
+
+        untyped_vec.reserve(10);
+        assert!(untyped_vec.cap == 40);
+        assert!(untyped_vec.len == 0);
+
+        for mut n in 0..10_u32 {
+            unsafe { untyped_vec.push_raw(&mut n as *mut u32 as *mut MaybeUninit<u8>) };
+        }
+        assert!(untyped_vec.cap == 40);
+
+        untyped_vec.reserve(1);
+        assert!(untyped_vec.cap == 80);
+        assert!(unsafe { untyped_vec.as_slice::<u32>() } == (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    pub fn shrink_to_fit() {
+        let mut untyped_vec = untyped_vec_new::<u32>();
+        for mut n in 0..5_u32 {
+            unsafe { untyped_vec.push_raw(&mut n as *mut u32 as *mut MaybeUninit<u8>) };
+        }
+        assert!(untyped_vec.cap == 32);
+
+        untyped_vec.shrink_to_fit();
+        assert!(untyped_vec.cap == 20);
+        assert!(unsafe { untyped_vec.as_slice::<u32>() } == [0, 1, 2, 3, 4]);
+
+        while untyped_vec.pop() {}
+        untyped_vec.shrink_to_fit();
+        assert!(untyped_vec.cap == 0);
+        assert!(untyped_vec.data == untyped_vec.type_info.dangling());
+
+        let mut zst = untyped_vec_new::<()>();
+        unsafe { zst.push_raw(&mut () as *mut () as *mut MaybeUninit<u8>) };
+        zst.shrink_to_fit();
+        assert!(zst.cap == 0);
+        assert!(zst.len == 1);
+    }
+
     #[test]
     pub fn push_raw() {
         let mut untyped_vec = untyped_vec_new::<u32>();
@@ -625,6 +776,34 @@ mod untyped_vec {
         assert!(unsafe { untyped_vec_2.as_slice::<Wrap>()[0].0 } == 12);
     }
 
+    #[test]
+    pub fn move_all_to_other_vec() {
+        let mut untyped_vec_1 = untyped_vec_new::<u32>();
+        let mut untyped_vec_2 = untyped_vec_new::<u32>();
+
+        for n in 0..3_u32 {
+            let mut data = ManuallyDrop::new(n);
+            unsafe {
+                untyped_vec_2.push_raw(&mut data as *mut _ as *mut MaybeUninit<u8>);
+            }
+        }
+        for n in 3..8_u32 {
+            let mut data = ManuallyDrop::new(n);
+            unsafe {
+                untyped_vec_1.push_raw(&mut data as *mut _ as *mut MaybeUninit<u8>);
+            }
+        }
+
+        unsafe {
+            untyped_vec_1.move_all_to_other_vec(&mut untyped_vec_2);
+        }
+
+        assert!(untyped_vec_1.len == 0);
+        assert!(untyped_vec_2.len == 8 * 4);
+        assert!(untyped_vec_2.cap >= untyped_vec_2.len);
+        assert!(unsafe { untyped_vec_2.as_slice::<u32>() } == [0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+
     #[test]
     pub fn remove() {
         let mut dropped = false;
@@ -647,4 +826,25 @@ mod untyped_vec {
         assert!(dropped == true);
         assert!(untyped_vec.len == 0);
     }
+
+    #[test]
+    pub fn swap_remove_into() {
+        let mut untyped_vec = untyped_vec_new::<u32>();
+        for mut n in [10_u32, 12, 14] {
+            unsafe { untyped_vec.push_raw(&mut n as *mut u32 as *mut MaybeUninit<u8>) };
+        }
+
+        let mut removed = 0_u32;
+        unsafe {
+            untyped_vec.swap_remove_into(0, &mut removed as *mut u32 as *mut MaybeUninit<u8>)
+        };
+        assert!(removed == 10);
+        assert!(unsafe { untyped_vec.as_slice::<u32>() } == [14, 12]);
+
+        unsafe {
+            untyped_vec.swap_remove_into(1, &mut removed as *mut u32 as *mut MaybeUninit<u8>)
+        };
+        assert!(removed == 12);
+        assert!(unsafe { untyped_vec.as_slice::<u32>() } == [14]);
+    }
 }